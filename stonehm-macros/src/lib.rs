@@ -1,6 +1,8 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, ItemFn, Attribute, Lit, Meta, Expr, Type, FnArg, ReturnType, PathArguments, GenericArgument, DeriveInput, Data, Fields};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, Token, ItemFn, Attribute, Lit, Meta, Expr, ExprLit, Type, FnArg, ReturnType, PathArguments, GenericArgument, DeriveInput, Data, Fields};
 
 /// Sanitize a type string to create a valid Rust identifier
 #[allow(dead_code)]
@@ -8,7 +10,6 @@ fn sanitize_type_for_identifier(type_str: &str) -> String {
     type_str
         .replace(['<', '>', ' ', ',', ':', ';', '(', ')', '[', ']', '{', '}', '&', '*'], "_")
         .replace("__", "_")
-        .trim_matches('_')
         .to_string()
 }
 
@@ -19,6 +20,7 @@ struct ResponseDoc {
     description: String,
     content: Option<ResponseContent>,
     examples: Option<Vec<ResponseExample>>,
+    headers: Vec<(String, String)>, // (name, description)
 }
 
 #[derive(Debug, Clone)]
@@ -34,6 +36,7 @@ struct ResponseExample {
     name: String,
     summary: Option<String>,
     value: String, // JSON or other content
+    default: bool, // shown first by docs UIs, flagged as `x-default`
 }
 
 #[derive(Debug, Clone)]
@@ -41,7 +44,9 @@ struct ResponseExample {
 struct ParameterDoc {
     name: String,
     description: String,
-    param_type: String, // path, query, header
+    param_type: String, // path, query, header, cookie
+    required: Option<bool>, // explicit `required` / `optional` marker
+    example: Option<String>, // `[example: value]` or `; example: value` suffix
 }
 
 #[derive(Debug, Clone)]
@@ -49,6 +54,15 @@ struct ParameterDoc {
 struct RequestBodyDoc {
     description: String,
     content_type: String,
+    fields: Vec<FieldDoc>,
+}
+
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+struct FieldDoc {
+    name: String,
+    field_type: String,
+    description: String,
 }
 
 #[derive(Debug, Clone)]
@@ -61,8 +75,69 @@ struct ParsedDocs {
     responses: Vec<ResponseDoc>,
 }
 
+/// Check whether an example value is complete, i.e. non-empty with balanced JSON brackets
+fn is_complete_value(value: &str) -> bool {
+    let mut depth = 0i32;
+    let mut in_string = false;
+    let mut escaped = false;
+    
+    for c in value.chars() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => depth += 1,
+            '}' | ']' => depth -= 1,
+            _ => {}
+        }
+    }
+    
+    !value.trim().is_empty() && depth <= 0 && !in_string
+}
+
+/// Parse a documented status as either a numeric code or a well-known name like `NOT_FOUND`
+fn parse_status_code(status: &str) -> Option<u16> {
+    if let Ok(code) = status.parse::<u16>() {
+        return Some(code);
+    }
+    let code = match status {
+        "OK" => 200,
+        "CREATED" => 201,
+        "ACCEPTED" => 202,
+        "NO_CONTENT" => 204,
+        "MOVED_PERMANENTLY" => 301,
+        "FOUND" => 302,
+        "NOT_MODIFIED" => 304,
+        "BAD_REQUEST" => 400,
+        "UNAUTHORIZED" => 401,
+        "FORBIDDEN" => 403,
+        "NOT_FOUND" => 404,
+        "METHOD_NOT_ALLOWED" => 405,
+        "CONFLICT" => 409,
+        "GONE" => 410,
+        "PAYLOAD_TOO_LARGE" => 413,
+        "UNSUPPORTED_MEDIA_TYPE" => 415,
+        "UNPROCESSABLE_ENTITY" => 422,
+        "TOO_MANY_REQUESTS" => 429,
+        "INTERNAL_SERVER_ERROR" => 500,
+        "NOT_IMPLEMENTED" => 501,
+        "BAD_GATEWAY" => 502,
+        "SERVICE_UNAVAILABLE" => 503,
+        "GATEWAY_TIMEOUT" => 504,
+        _ => return None,
+    };
+    Some(code)
+}
+
 /// Extract documentation from attributes
-#[allow(dead_code)]
 fn extract_docs(attrs: &[Attribute]) -> ParsedDocs {
     let mut lines = Vec::new();
     
@@ -98,6 +173,8 @@ fn extract_docs(attrs: &[Attribute]) -> ParsedDocs {
     let mut request_body = None;
     let mut responses = Vec::new();
     let mut current_section = "";
+    let mut collecting_value = false;
+    let mut collecting_headers = false;
     
     for (i, line) in lines.iter().enumerate() {
         if i == 0 {
@@ -131,15 +208,35 @@ fn extract_docs(attrs: &[Attribute]) -> ParsedDocs {
                     if let Some(paren_start) = param_text.find('(') {
                         if let Some(paren_end) = param_text.find(')') {
                             let name = param_text[..paren_start].trim();
-                            let param_type = param_text[paren_start + 1..paren_end].trim();
+                            // "(query, u32, required)": location first, then type and markers
+                            let mut qualifiers = param_text[paren_start + 1..paren_end].split(',').map(str::trim);
+                            let param_type = qualifiers.next().unwrap_or_default();
+                            let required = qualifiers.fold(None, |required, qualifier| match qualifier {
+                                "required" => Some(true),
+                                "optional" => Some(false),
+                                _ => required,
+                            });
                             
                             if let Some(colon_pos) = param_text[paren_end..].find(':') {
-                                let description = param_text[paren_end + colon_pos + 1..].trim();
+                                let mut description = param_text[paren_end + colon_pos + 1..].trim();
+                                let mut example = None;
+                                if let Some((text, value)) = description.split_once("; example:") {
+                                    example = Some(value.trim().to_string());
+                                    description = text.trim();
+                                } else if let Some((text, value)) = description
+                                    .strip_suffix(']')
+                                    .and_then(|rest| rest.rsplit_once("[example:"))
+                                {
+                                    example = Some(value.trim().to_string());
+                                    description = text.trim();
+                                }
                                 
                                 parameters.push(ParameterDoc {
                                     name: name.to_string(),
                                     description: description.to_string(),
-                                    param_type: param_type.to_string(),
+                                    param_type: param_type.to_ascii_lowercase(),
+                                    required,
+                                    example,
                                 });
                             }
                         }
@@ -153,8 +250,22 @@ fn extract_docs(attrs: &[Attribute]) -> ParsedDocs {
                     request_body = Some(RequestBodyDoc {
                         description: String::new(),
                         content_type,
+                        fields: Vec::new(),
                     });
                 } else if let Some(ref mut body) = request_body {
+                    // Field lines like "- name (string): Full name" describe schema properties
+                    if let Some(field_text) = line.strip_prefix("- ") {
+                        if let (Some(paren_start), Some(paren_end)) = (field_text.find('('), field_text.find(')')) {
+                            if let Some(colon_pos) = field_text[paren_end..].find(':') {
+                                body.fields.push(FieldDoc {
+                                    name: field_text[..paren_start].trim().to_string(),
+                                    field_type: field_text[paren_start + 1..paren_end].trim().to_string(),
+                                    description: field_text[paren_end + colon_pos + 1..].trim().to_string(),
+                                });
+                                continue;
+                            }
+                        }
+                    }
                     if !line.is_empty() {
                         if !body.description.is_empty() {
                             body.description.push(' ');
@@ -164,15 +275,33 @@ fn extract_docs(attrs: &[Attribute]) -> ParsedDocs {
                 }
             },
             "responses" => {
+                // Continuation lines of a multi-line example value
+                if collecting_value && !line.starts_with("- ") {
+                    let example = responses
+                        .last_mut()
+                        .and_then(|response: &mut ResponseDoc| response.examples.as_mut())
+                        .and_then(|examples| examples.last_mut());
+                    if let Some(example) = example {
+                        if !example.value.is_empty() {
+                            example.value.push(' ');
+                        }
+                        example.value.push_str(line);
+                        collecting_value = !is_complete_value(&example.value);
+                    }
+                    continue;
+                }
+                collecting_value = false;
+                
                 // Parse response lines - both simple and elaborate formats
-                if line.starts_with("- ") || line.starts_with("* ") {
+                if (line.starts_with("- ") || line.starts_with("* ")) && !line.starts_with("- name:") {
+                    collecting_headers = false;
                     let response_text = line[2..].trim();
                     
                     if let Some(colon_pos) = response_text.find(':') {
                         let status_part = response_text[..colon_pos].trim();
                         let after_colon = response_text[colon_pos + 1..].trim();
                         
-                        if let Ok(status_code) = status_part.parse::<u16>() {
+                        if let Some(status_code) = parse_status_code(status_part) {
                             if after_colon.is_empty() {
                                 // Elaborate format - status code with no immediate description
                                 // We'll parse the following lines as YAML-like content
@@ -181,6 +310,7 @@ fn extract_docs(attrs: &[Attribute]) -> ParsedDocs {
                                     description: String::new(), // Will be filled in by subsequent lines
                                     content: None,
                                     examples: None,
+                                    headers: Vec::new(),
                                 });
                             } else {
                                 // Simple format - status code: description
@@ -189,11 +319,13 @@ fn extract_docs(attrs: &[Attribute]) -> ParsedDocs {
                                     description: after_colon.to_string(),
                                     content: None,
                                     examples: None,
+                                    headers: Vec::new(),
                                 });
                             }
                         }
                     }
                 } else if !responses.is_empty() && (
+                    line.starts_with("headers:") || 
                     line.starts_with("description:") || 
                     line.starts_with("content:") || 
                     line.starts_with("application/json:") || 
@@ -204,9 +336,11 @@ fn extract_docs(attrs: &[Attribute]) -> ParsedDocs {
                     line.starts_with("- name:") || 
                     line.starts_with("name:") || 
                     line.starts_with("summary:") || 
-                    line.starts_with("value:")
+                    line.starts_with("value:") || 
+                    line.starts_with("default:")
                 ) {
                     // YAML-like property line - part of elaborate response format
+                    collecting_headers = line.starts_with("headers:");
                     if let Some(last_response) = responses.last_mut() {
                         if let Some(desc) = line.strip_prefix("description:") {
                             let desc = desc.trim().trim_matches('"');
@@ -262,6 +396,7 @@ fn extract_docs(attrs: &[Attribute]) -> ParsedDocs {
                                     name: name.trim_matches('"').to_string(),
                                     summary: None,
                                     value: String::new(),
+                                    default: false,
                                 });
                             }
                         } else if line.starts_with("summary:") && last_response.examples.is_some() {
@@ -272,16 +407,27 @@ fn extract_docs(attrs: &[Attribute]) -> ParsedDocs {
                                     last_example.summary = Some(summary.to_string());
                                 }
                             }
+                        } else if let (Some(flag), Some(examples)) = (line.strip_prefix("default:"), last_response.examples.as_mut()) {
+                            // Mark the last example as the one to show first
+                            if let Some(last_example) = examples.last_mut() {
+                                last_example.default = flag.trim() == "true";
+                            }
                         } else if line.starts_with("value:") && last_response.examples.is_some() {
                             // Add value to the last example
                             let value = line[6..].trim().trim_matches('"');
                             if let Some(ref mut examples) = last_response.examples {
                                 if let Some(last_example) = examples.last_mut() {
                                     last_example.value = value.to_string();
+                                    collecting_value = !is_complete_value(value);
                                 }
                             }
                         }
                     }
+                } else if collecting_headers {
+                    // Header lines like "Location: URL of the job status resource"
+                    if let (Some(last_response), Some((name, description))) = (responses.last_mut(), line.split_once(':')) {
+                        last_response.headers.push((name.trim().to_string(), description.trim().to_string()));
+                    }
                 }
             },
             _ => {
@@ -310,23 +456,103 @@ fn extract_docs(attrs: &[Attribute]) -> ParsedDocs {
 
 /// Extract request body type from function parameters
 fn extract_request_body_type(inputs: &syn::punctuated::Punctuated<FnArg, syn::token::Comma>) -> Option<String> {
+    for input in inputs {
+        let FnArg::Typed(pat_type) = input else {
+            continue;
+        };
+        let Type::Path(type_path) = &*pat_type.ty else {
+            continue;
+        };
+        let Some(segment) = type_path.path.segments.last() else {
+            continue;
+        };
+        // State, extension and non-body extractors never carry the request body
+        if ["State", "Extension", "Query", "Path", "HeaderMap", "TypedHeader"].iter().any(|name| segment.ident == name) {
+            continue;
+        }
+        // A destructuring pattern must unwrap the same `Json` wrapper, e.g. `Json(body): Json<T>`
+        if let syn::Pat::TupleStruct(pattern) = &*pat_type.pat {
+            if pattern.path.segments.last().is_none_or(|s| s.ident != "Json") {
+                continue;
+            }
+        }
+        // Look for Json<T> pattern
+        if segment.ident == "Json" {
+            if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                if let Some(GenericArgument::Type(inner_type)) = args.args.first() {
+                    return Some(quote!(#inner_type).to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Extract the struct `T` of a `Query<T>` extractor, whose fields become query parameters
+fn extract_query_type(inputs: &syn::punctuated::Punctuated<FnArg, syn::token::Comma>) -> Option<String> {
+    inputs.iter().find_map(|input| {
+        let FnArg::Typed(pat_type) = input else {
+            return None;
+        };
+        let Type::Path(type_path) = &*pat_type.ty else {
+            return None;
+        };
+        let segment = type_path.path.segments.last().filter(|segment| segment.ident == "Query")?;
+        match &segment.arguments {
+            PathArguments::AngleBracketed(args) => match args.args.first() {
+                Some(GenericArgument::Type(inner_type)) => nested_schema_name(inner_type),
+                _ => None,
+            },
+            _ => None,
+        }
+    })
+}
+
+/// Extract the OpenAPI types of a `Path<T>` extractor as a JSON array, one entry per
+/// path segment for tuples like `Path<(u32, String)>`; `"[]"` when there is no such extractor.
+/// A struct like `Path<UserPath>` is named instead, as `{"struct":"UserPath"}`, so the router
+/// can type segments from its registered schema's fields
+fn extract_path_param_types(inputs: &syn::punctuated::Punctuated<FnArg, syn::token::Comma>) -> String {
     for input in inputs {
         if let FnArg::Typed(pat_type) = input {
             if let Type::Path(type_path) = &*pat_type.ty {
-                // Look for Json<T> pattern
                 if let Some(segment) = type_path.path.segments.last() {
-                    if segment.ident == "Json" {
+                    if segment.ident == "Path" {
                         if let PathArguments::AngleBracketed(args) = &segment.arguments {
                             if let Some(GenericArgument::Type(inner_type)) = args.args.first() {
-                                return Some(quote!(#inner_type).to_string());
+                                if let Some(name) = nested_schema_name(inner_type) {
+                                    return format!("{{\"struct\":\"{name}\"}}");
+                                }
                             }
+                            let types: Vec<&'static str> = match args.args.first() {
+                                Some(GenericArgument::Type(Type::Tuple(tuple))) => {
+                                    tuple.elems.iter().map(path_param_type).collect()
+                                },
+                                Some(GenericArgument::Type(inner_type)) => vec![path_param_type(inner_type)],
+                                _ => Vec::new(),
+                            };
+                            let types: Vec<String> = types.iter().map(|t| format!("\"{t}\"")).collect();
+                            return format!("[{}]", types.join(","));
                         }
                     }
                 }
             }
         }
     }
-    None
+    "[]".to_string()
+}
+
+/// OpenAPI type of a single path segment, falling back to `"string"`
+fn path_param_type(ty: &Type) -> &'static str {
+    let Type::Path(type_path) = ty else {
+        return "string";
+    };
+    match type_path.path.segments.last().map(|segment| segment.ident.to_string()).as_deref() {
+        Some("i8" | "i16" | "i32" | "i64" | "isize" | "u8" | "u16" | "u32" | "u64" | "usize") => "integer",
+        Some("f32" | "f64") => "number",
+        Some("bool") => "boolean",
+        _ => "string",
+    }
 }
 
 /// Extract response and error types from function return type
@@ -337,45 +563,381 @@ fn extract_response_and_error_types(output: &ReturnType) -> (Option<String>, Opt
                 // Handle Result<T, E> pattern
                 if segment.ident == "Result" {
                     if let PathArguments::AngleBracketed(args) = &segment.arguments {
-                        let mut response_type = None;
-                        let mut error_type = None;
-                        
                         // First argument is success type
-                        if let Some(GenericArgument::Type(Type::Path(ok_path))) = args.args.first() {
-                            // Check if it's Json<T>
-                            if let Some(json_segment) = ok_path.path.segments.last() {
-                                if json_segment.ident == "Json" {
-                                    if let PathArguments::AngleBracketed(json_args) = &json_segment.arguments {
-                                        if let Some(GenericArgument::Type(inner_type)) = json_args.args.first() {
-                                            response_type = Some(quote!(#inner_type).to_string());
-                                        }
-                                    }
-                                }
-                            }
-                        }
+                        let response_type = match args.args.first() {
+                            Some(GenericArgument::Type(ok_type)) => json_body_type(ok_type),
+                            _ => None,
+                        };
                         
                         // Second argument is error type
-                        if let Some(GenericArgument::Type(err_type)) = args.args.iter().nth(1) {
-                            error_type = Some(quote!(#err_type).to_string());
-                        }
+                        let error_type = match args.args.iter().nth(1) {
+                            Some(GenericArgument::Type(err_type)) => Some(quote!(#err_type).to_string()),
+                            _ => None,
+                        };
                         
                         return (response_type, error_type);
                     }
                 }
-                // Handle direct Json<T> pattern (no Result wrapper)
-                else if segment.ident == "Json" {
-                    if let PathArguments::AngleBracketed(args) = &segment.arguments {
-                        if let Some(GenericArgument::Type(inner_type)) = args.args.first() {
-                            return (Some(quote!(#inner_type).to_string()), None);
+            }
+        }
+        // Handle direct Json<T> or (StatusCode, Json<T>) patterns (no Result wrapper)
+        return (json_body_type(return_type), None);
+    }
+    (None, None)
+}
+
+/// `T` of a `Json<T>` body, on its own or as an element of a tuple such as `(StatusCode, Json<T>)`
+fn json_body_type(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Tuple(tuple) => tuple.elems.iter().find_map(json_body_type),
+        Type::Path(type_path) => {
+            let segment = type_path.path.segments.last()?;
+            if segment.ident != "Json" {
+                return None;
+            }
+            match &segment.arguments {
+                PathArguments::AngleBracketed(args) => match args.args.first() {
+                    Some(GenericArgument::Type(inner_type)) => Some(quote!(#inner_type).to_string()),
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Whether a handler returns an `Sse<...>` event stream, directly or as a `Result`'s success type
+fn returns_event_stream(output: &ReturnType) -> bool {
+    let ReturnType::Type(_, return_type) = output else {
+        return false;
+    };
+    let Type::Path(type_path) = &**return_type else {
+        return false;
+    };
+    let Some(segment) = type_path.path.segments.last() else {
+        return false;
+    };
+    if segment.ident == "Sse" {
+        return true;
+    }
+    if segment.ident != "Result" {
+        return false;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => matches!(
+            args.args.first(),
+            Some(GenericArgument::Type(Type::Path(ok_path))) if ok_path.path.segments.last().is_some_and(|s| s.ident == "Sse")
+        ),
+        _ => false,
+    }
+}
+
+/// Whether the success part of a return type carries a `StatusCode`, e.g. `(StatusCode, Json<T>)`
+fn returns_status_code(output: &ReturnType) -> bool {
+    fn is_status_code(ty: &Type) -> bool {
+        match ty {
+            Type::Path(type_path) => type_path.path.segments.last().is_some_and(|s| s.ident == "StatusCode"),
+            Type::Tuple(tuple) => tuple.elems.iter().any(is_status_code),
+            _ => false,
+        }
+    }
+    let ReturnType::Type(_, return_type) = output else {
+        return false;
+    };
+    if let Type::Path(type_path) = &**return_type {
+        if let Some(segment) = type_path.path.segments.last().filter(|s| s.ident == "Result") {
+            return match &segment.arguments {
+                PathArguments::AngleBracketed(args) => {
+                    matches!(args.args.first(), Some(GenericArgument::Type(ok_type)) if is_status_code(ok_type))
+                }
+                _ => false,
+            };
+        }
+    }
+    is_status_code(return_type)
+}
+
+/// The 2xx status a handler body returns through `StatusCode::CREATED` and the like, when it
+/// names exactly one
+fn body_success_status(block: &syn::Block) -> Option<u16> {
+    fn collect(tokens: proc_macro2::TokenStream, found: &mut Vec<u16>) {
+        let tokens: Vec<proc_macro2::TokenTree> = tokens.into_iter().collect();
+        for (i, token) in tokens.iter().enumerate() {
+            match token {
+                proc_macro2::TokenTree::Group(group) => collect(group.stream(), found),
+                proc_macro2::TokenTree::Ident(ident) if ident == "StatusCode" => {
+                    if let (Some(proc_macro2::TokenTree::Punct(colon)), Some(proc_macro2::TokenTree::Ident(name))) =
+                        (tokens.get(i + 2), tokens.get(i + 3))
+                    {
+                        let status = match name.to_string().as_str() {
+                            "OK" => 200,
+                            "CREATED" => 201,
+                            "ACCEPTED" => 202,
+                            "NON_AUTHORITATIVE_INFORMATION" => 203,
+                            "NO_CONTENT" => 204,
+                            "RESET_CONTENT" => 205,
+                            "PARTIAL_CONTENT" => 206,
+                            _ => continue,
+                        };
+                        if colon.as_char() == ':' && !found.contains(&status) {
+                            found.push(status);
                         }
                     }
                 }
+                _ => {}
             }
         }
     }
-    (None, None)
+    let mut found = Vec::new();
+    collect(quote!(#block), &mut found);
+    match found.as_slice() {
+        [status] => Some(*status),
+        _ => None,
+    }
+}
+
+/// Build a JSON map of status code to named examples, e.g. `{"400":{"missing_field":{"value":{...}}}}`
+fn response_examples_json(responses: &[ResponseDoc]) -> String {
+    let mut by_status = serde_json::Map::new();
+    
+    for response in responses {
+        let Some(examples) = &response.examples else {
+            continue;
+        };
+        
+        let mut entries = serde_json::Map::new();
+        for example in examples {
+            let mut entry = serde_json::Map::new();
+            if let Some(summary) = &example.summary {
+                entry.insert("summary".to_string(), serde_json::Value::String(summary.clone()));
+            }
+            // Values that aren't valid JSON are documented as plain strings
+            let value = serde_json::from_str(&example.value)
+                .unwrap_or_else(|_| serde_json::Value::String(example.value.clone()));
+            entry.insert("value".to_string(), value);
+            // Example maps are unordered in OpenAPI 3.0, so the default is flagged instead
+            if example.default {
+                entry.insert("x-default".to_string(), serde_json::Value::Bool(true));
+            }
+            entries.insert(example.name.clone(), serde_json::Value::Object(entry));
+        }
+        
+        if !entries.is_empty() {
+            by_status.insert(response.status_code.to_string(), serde_json::Value::Object(entries));
+        }
+    }
+    
+    serde_json::Value::Object(by_status).to_string()
+}
+
+/// Serialize response headers and explicit `schema:` names as a JSON object keyed by status code
+fn response_details_json(responses: &[ResponseDoc]) -> String {
+    let mut by_status = serde_json::Map::new();
+    
+    for response in responses {
+        let mut details = serde_json::Map::new();
+        if !response.headers.is_empty() {
+            let headers: serde_json::Map<String, serde_json::Value> = response.headers
+                .iter()
+                .map(|(name, description)| (name.clone(), serde_json::Value::String(description.clone())))
+                .collect();
+            details.insert("headers".to_string(), serde_json::Value::Object(headers));
+        }
+        if let Some(schema) = response.content.as_ref().and_then(|content| content.schema.as_ref()) {
+            details.insert("schema".to_string(), serde_json::Value::String(schema.clone()));
+        }
+        if !details.is_empty() {
+            by_status.insert(response.status_code.to_string(), serde_json::Value::Object(details));
+        }
+    }
+    
+    serde_json::Value::Object(by_status).to_string()
+}
+
+/// Pull `examples:` blocks out of `# Request Body` lines, keyed by the `Content-Type:` they follow
+///
+/// Returns the remaining lines and a JSON object of media type to named examples.
+fn split_request_examples(lines: &[String]) -> (Vec<String>, String) {
+    let mut remaining = Vec::new();
+    let mut by_media_type = serde_json::Map::new();
+    let mut media_type = "application/json".to_string();
+    let mut examples: Vec<ResponseExample> = Vec::new();
+    let mut in_examples = false;
+    let mut collecting_value = false;
+    
+    fn flush(by_media_type: &mut serde_json::Map<String, serde_json::Value>, media_type: &str, examples: &mut Vec<ResponseExample>) {
+        if examples.is_empty() {
+            return;
+        }
+        let entries = by_media_type
+            .entry(media_type.to_string())
+            .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+        for example in examples.drain(..) {
+            let mut entry = serde_json::Map::new();
+            if let Some(summary) = example.summary {
+                entry.insert("summary".to_string(), serde_json::Value::String(summary));
+            }
+            // Values that aren't valid JSON (e.g. form bodies) are documented as plain strings
+            let value = serde_json::from_str(&example.value)
+                .unwrap_or(serde_json::Value::String(example.value));
+            entry.insert("value".to_string(), value);
+            if example.default {
+                entry.insert("x-default".to_string(), serde_json::Value::Bool(true));
+            }
+            entries[&example.name] = serde_json::Value::Object(entry);
+        }
+    }
+    
+    for line in lines {
+        if collecting_value {
+            if let Some(example) = examples.last_mut() {
+                example.value.push(' ');
+                example.value.push_str(line);
+                collecting_value = !is_complete_value(&example.value);
+            }
+            continue;
+        }
+        
+        if let Some(stripped) = line.strip_prefix("Content-Type:") {
+            flush(&mut by_media_type, &media_type, &mut examples);
+            media_type = stripped.trim().to_string();
+            in_examples = false;
+            remaining.push(line.clone());
+        } else if line == "examples:" {
+            in_examples = true;
+        } else if in_examples {
+            if let Some(name) = line.strip_prefix("- name:") {
+                examples.push(ResponseExample {
+                    name: name.trim().to_string(),
+                    summary: None,
+                    value: String::new(),
+                    default: false,
+                });
+            } else if let Some(example) = examples.last_mut() {
+                if let Some(summary) = line.strip_prefix("summary:") {
+                    example.summary = Some(summary.trim().to_string());
+                } else if let Some(flag) = line.strip_prefix("default:") {
+                    example.default = flag.trim() == "true";
+                } else if let Some(value) = line.strip_prefix("value:") {
+                    example.value = value.trim().to_string();
+                    collecting_value = !is_complete_value(&example.value);
+                }
+            }
+        } else {
+            remaining.push(line.clone());
+        }
+    }
+    flush(&mut by_media_type, &media_type, &mut examples);
+    
+    (remaining, serde_json::Value::Object(by_media_type).to_string())
+}
+
+/// Arguments accepted by `#[api_handler(...)]`
+#[derive(Debug, Default)]
+struct HandlerArgs {
+    tags: Vec<String>,
+    echo: bool,
+    internal: bool,
+    operation_id: Option<String>,
+    security: Option<String>,
+    scopes: Vec<String>,
+    response_content_type: Option<String>,
+    success_status: Option<u16>,
+}
+
+/// Parse `#[api_handler(...)]` arguments: string literals are tags, bare identifiers are flags,
+/// and `operation_id = "..."` overrides the generated operationId
+fn parse_handler_args(attr: proc_macro2::TokenStream) -> syn::Result<HandlerArgs> {
+    let mut args = HandlerArgs::default();
+    if attr.is_empty() {
+        return Ok(args);
+    }
+    
+    let exprs = Punctuated::<Expr, Token![,]>::parse_terminated.parse2(attr)?;
+    for expr in exprs {
+        match &expr {
+            Expr::Lit(lit) => match &lit.lit {
+                Lit::Str(s) => {
+                    let tag = s.value();
+                    if !tag.is_empty() {
+                        args.tags.push(tag);
+                    }
+                },
+                _ => return Err(syn::Error::new_spanned(&expr, "expected a string literal tag")),
+            },
+            Expr::Path(path) if path.path.is_ident("echo") => args.echo = true,
+            Expr::Path(path) if path.path.is_ident("internal") => args.internal = true,
+            Expr::Assign(assign) => {
+                let Expr::Path(key) = &*assign.left else {
+                    return Err(syn::Error::new_spanned(&expr, "unsupported api_handler argument"));
+                };
+                if key.path.is_ident("success_status") {
+                    let Expr::Lit(ExprLit { lit: Lit::Int(value), .. }) = &*assign.right else {
+                        return Err(syn::Error::new_spanned(&assign.right, "expected a status code"));
+                    };
+                    let status = value.base10_parse::<u16>()?;
+                    if !(200..300).contains(&status) {
+                        return Err(syn::Error::new_spanned(value, "success_status must be a 2xx status code"));
+                    }
+                    args.success_status = Some(status);
+                    continue;
+                }
+                let Expr::Lit(ExprLit { lit: Lit::Str(value), .. }) = &*assign.right else {
+                    return Err(syn::Error::new_spanned(&assign.right, "expected a string literal"));
+                };
+                if key.path.is_ident("operation_id") {
+                    args.operation_id = Some(value.value());
+                } else if key.path.is_ident("security") {
+                    args.security = Some(value.value());
+                } else if key.path.is_ident("response_content_type") {
+                    args.response_content_type = Some(value.value());
+                } else {
+                    return Err(syn::Error::new_spanned(&expr, "unsupported api_handler argument"));
+                }
+            },
+            Expr::Call(call) if matches!(&*call.func, Expr::Path(path) if path.path.is_ident("scopes")) => {
+                for scope in &call.args {
+                    match scope {
+                        Expr::Lit(ExprLit { lit: Lit::Str(s), .. }) => args.scopes.push(s.value()),
+                        _ => return Err(syn::Error::new_spanned(scope, "expected a string literal scope")),
+                    }
+                }
+            },
+            _ => return Err(syn::Error::new_spanned(&expr, "unsupported api_handler argument")),
+        }
+    }
+    if args.security.is_none() && !args.scopes.is_empty() {
+        return Err(syn::Error::new(proc_macro2::Span::call_site(), "scopes(...) requires security = \"scheme\""));
+    }
+    Ok(args)
+}
+
+/// The operation's security requirement array, e.g. `[{"oauth2":["read:users"]}]`
+///
+/// Each `# Security` line (`- bearerAuth` or `- oauth2: read:users, write:users`) is an
+/// alternative requirement, alongside the one from `security = "..."`.
+fn security_requirements_json(scheme: Option<&str>, scopes: &[String], security_lines: &[String]) -> String {
+    let mut requirements = Vec::new();
+    if let Some(scheme) = scheme {
+        requirements.push(serde_json::json!({ scheme: scopes }));
+    }
+    for line in security_lines {
+        let (scheme, scopes) = line.split_once(':').unwrap_or((line, ""));
+        let scopes: Vec<&str> = scopes.split(',').map(str::trim).filter(|s| !s.is_empty()).collect();
+        requirements.push(serde_json::json!({ scheme.trim(): scopes }));
+    }
+    serde_json::Value::Array(requirements).to_string()
 }
 
+/// Split handler doc lines into the summary and the description prose before any `#` section;
+/// missing parts stay empty so the spec omits them instead of showing placeholder text
+fn summary_and_description(doc_lines: &[String]) -> (String, Vec<String>) {
+    let mut lines = doc_lines.iter().take_while(|line| !line.starts_with('#'));
+    let summary = lines.next().cloned().unwrap_or_default();
+    let description_lines = lines.filter(|line| !line.trim().is_empty()).cloned().collect();
+    (summary, description_lines)
+}
 
 /// Simple api_handler attribute that works with current simplified implementation
 /// 
@@ -383,23 +945,26 @@ fn extract_response_and_error_types(output: &ReturnType) -> (Option<String>, Opt
 /// - `#[api_handler]` - No tags
 /// - `#[api_handler("tag1")]` - Single tag
 /// - `#[api_handler("tag1", "tag2")]` - Multiple tags
+/// - `#[api_handler(echo)]` - Document the success response with the request body schema
+/// - `#[api_handler(operation_id = "listUsers")]` - Use this operationId verbatim
+/// - `#[api_handler(internal)]` - Mark the operation `x-internal` and leave it out of the public spec
+/// - `#[api_handler(security = "oauth2", scopes("read:users"))]` - Require a security scheme with scopes
+/// - `#[api_handler(response_content_type = "application/vnd.myapi.v1+json")]` - Media type of the success response
+/// - `#[api_handler(success_status = 201)]` - Status of the default success response when no `# Responses` are documented;
+///   handlers returning `(StatusCode, ...)` default to the one 2xx `StatusCode::...` their body names
+/// 
+/// A `# Notes` (or `# Remarks`) doc section is appended to the description after a blank line.
 #[proc_macro_attribute]
 pub fn api_handler(attr: TokenStream, item: TokenStream) -> TokenStream {
     let input = parse_macro_input!(item as ItemFn);
     let fn_name = &input.sig.ident;
     
-    // Parse tags from attribute arguments
-    let tags: Vec<String> = if attr.is_empty() {
-        Vec::new()
-    } else {
-        // Parse comma-separated string literals
-        let attr_str = attr.to_string();
-        attr_str
-            .split(',')
-            .map(|s| s.trim().trim_matches('"').trim_matches('\'').to_string())
-            .filter(|s| !s.is_empty())
-            .collect()
+    // Parse tags and flags from attribute arguments
+    let args = match parse_handler_args(attr.into()) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
     };
+    let tags = args.tags;
     
     // Extract documentation from doc comments
     let mut doc_lines = Vec::new();
@@ -420,88 +985,45 @@ pub fn api_handler(attr: TokenStream, item: TokenStream) -> TokenStream {
     }
     
     let fn_name_str = fn_name.to_string();
-    let summary = doc_lines.first().unwrap_or(&"No summary".to_string()).clone();
-    
-    // Extract description (everything after summary but before any # sections)
-    let mut description_lines = Vec::new();
-    for (i, line) in doc_lines.iter().enumerate() {
-        if i == 0 {
-            continue; // Skip summary
-        }
-        if line.starts_with("#") {
-            break; // Stop at first section header
-        }
-        if !line.trim().is_empty() {
-            description_lines.push(line.clone());
-        }
-    }
-    let description = if description_lines.is_empty() {
-        "No description".to_string()
-    } else {
-        description_lines.join(" ")
-    };
+    let (summary, description_lines) = summary_and_description(&doc_lines);
+    let mut description = description_lines.join(" ");
     
     // Simple parameter and response parsing from doc string
-    let mut parameters = Vec::new();
-    let mut responses = Vec::new();
-    let mut request_body = Vec::new();
+    let DocSections { parameters, responses, request_body, security, notes } = doc_sections(&doc_lines);
     
-    let mut current_section = "";
-    for line in &doc_lines {
-        if line.starts_with("# Parameters") {
-            current_section = "parameters";
-        } else if line.starts_with("# Responses") {
-            current_section = "responses";  
-        } else if line.starts_with("# Request Body") {
-            current_section = "request_body";
-        } else if line.starts_with("- ") && current_section == "parameters" {
-            parameters.push(line[2..].to_string());
-        } else if line.starts_with("- ") && current_section == "responses" {
-            let response_line = line[2..].to_string();
-            
-            // Handle both simple format "- 200: Success" and complex format "- 404:"
-            if response_line.contains(":") {
-                if let Some(colon_pos) = response_line.find(':') {
-                    let status_part = response_line[..colon_pos].trim();
-                    let desc_part = response_line[colon_pos + 1..].trim();
-                    
-                    if status_part.chars().all(|c| c.is_ascii_digit()) && status_part.len() == 3 {
-                        if desc_part.is_empty() {
-                            // Complex format - will collect description from following lines
-                            responses.push(format!("{status_part}:"));
-                        } else {
-                            // Simple format
-                            responses.push(response_line);
-                        }
-                    } else {
-                        responses.push(response_line);
-                    }
-                } else {
-                    responses.push(response_line);
-                }
-            } else {
-                responses.push(response_line);
-            }
-        } else if current_section == "responses" && !line.starts_with("#") && !line.starts_with("- ") {
-            // Handle YAML-style continuation lines for complex responses
-            if line.trim().starts_with("description:") {
-                let desc = line.trim().strip_prefix("description:").unwrap_or("").trim();
-                // Update the last response entry with the description
-                if let Some(last_response) = responses.last_mut() {
-                    if last_response.ends_with(':') {
-                        let status_code = last_response.trim_end_matches(':');
-                        *last_response = format!("{status_code}: {desc}");
-                    }
-                }
-            }
-        } else if current_section == "request_body" && !line.starts_with("#") {
-            request_body.push(line.clone());
+    // Notes follow the main prose after a blank line, keeping their line breaks
+    if !notes.is_empty() {
+        if description_lines.is_empty() {
+            description = notes.join("\n");
+        } else {
+            description = format!("{description}\n\n{}", notes.join("\n"));
         }
     }
     
     // Extract type information from function signature
     let request_body_type = extract_request_body_type(&input.sig.inputs);
-    let (_response_type, _error_type) = extract_response_and_error_types(&input.sig.output);
+    let path_param_types = extract_path_param_types(&input.sig.inputs);
+    let query_type = extract_query_type(&input.sig.inputs).unwrap_or_default();
+    let (response_type, error_type) = extract_response_and_error_types(&input.sig.output);
+    
+    // Echo endpoints reuse the request schema when no response type is declared
+    let echo = args.echo && request_body_type.is_some() && response_type.is_none();
+    let operation_id = args.operation_id.unwrap_or_default();
+    // Sse<...> handlers stream text/event-stream unless a media type is given explicitly
+    let response_content_type = args.response_content_type.unwrap_or_else(|| {
+        if returns_event_stream(&input.sig.output) { "text/event-stream".to_string() } else { String::new() }
+    });
+    // A handler returning `(StatusCode, ...)` defaults to the one 2xx status its body names
+    let body_status = if returns_status_code(&input.sig.output) { body_success_status(&input.block) } else { None };
+    let success_status = args.success_status.or(body_status).unwrap_or(200);
+    let request_body_type_str = request_body_type.clone().unwrap_or_default();
+    let response_type_str = response_type.unwrap_or_default();
+    let error_type_str = error_type.unwrap_or_default();
+    let internal = args.internal;
+    let security_json = security_requirements_json(args.security.as_deref(), &args.scopes, &security);
+    
+    // Examples are emitted per media type rather than as request body text
+    let (request_body, request_examples_json) = split_request_examples(&request_body);
     
     // Include type information in the request body documentation
     let mut enhanced_request_body = request_body.clone();
@@ -510,6 +1032,11 @@ pub fn api_handler(attr: TokenStream, item: TokenStream) -> TokenStream {
         enhanced_request_body.insert(0, format!("Type: {req_type}"));
     }
     
+    // Named response examples from the elaborate response format
+    let parsed_docs = extract_docs(&input.attrs);
+    let response_examples_json = response_examples_json(&parsed_docs.responses);
+    let response_details_json = response_details_json(&parsed_docs.responses);
+    
     let parameters_json = format!("[{}]", parameters.iter().map(|p| format!("\"{}\"", p.replace("\"", "\\\""))).collect::<Vec<_>>().join(","));
     let responses_json = format!("[{}]", responses.iter().map(|r| format!("\"{}\"", r.replace("\"", "\\\""))).collect::<Vec<_>>().join(","));
     let request_body_json = format!("[{}]", enhanced_request_body.iter().map(|rb| format!("\"{}\"", rb.replace("\"", "\\\""))).collect::<Vec<_>>().join(","));
@@ -528,6 +1055,21 @@ pub fn api_handler(attr: TokenStream, item: TokenStream) -> TokenStream {
                 responses: #responses_json,
                 request_body: #request_body_json,
                 tags: #tags_json,
+                echo: #echo,
+                response_examples: #response_examples_json,
+                operation_id: #operation_id,
+                internal: #internal,
+                request_examples: #request_examples_json,
+                security: #security_json,
+                response_details: #response_details_json,
+                path_param_types: #path_param_types,
+                response_content_type: #response_content_type,
+                success_status: #success_status,
+                request_body_type: #request_body_type_str,
+                response_type: #response_type_str,
+                error_type: #error_type_str,
+                query_type: #query_type,
+                module_path: module_path!(),
             }
         }
     };
@@ -548,687 +1090,2438 @@ pub fn documented_router(_input: TokenStream) -> TokenStream {
     TokenStream::from(output)
 }
 
-/// Derive macro for automatic JSON schema generation.
-/// 
-/// This derive macro automatically implements the `StonehmSchema` trait for your types,
-/// enabling automatic JSON schema generation for OpenAPI specifications. Use this
-/// on all request and response types that you want to appear in your OpenAPI spec.
-/// 
-/// # Type Support
-/// 
-/// Supported Rust types and their JSON schema mappings:
-/// - `String`, `&str` → `"string"`
-/// - `i32`, `i64`, `u32`, `u64`, etc. → `"integer"`
-/// - `f32`, `f64` → `"number"`
-/// - `bool` → `"boolean"`
-/// - `Option<T>` → makes field optional
-/// - `Vec<T>` → `"array"` with item schema
-/// - Nested structs → object references
-/// - Enums → `"string"` (basic support)
-/// 
-/// # Examples
-/// 
-/// ## Basic Struct
-/// 
-/// ```rust
-/// use serde::Serialize;
-/// use stonehm_macros::StoneSchema;
-/// 
-/// #[derive(Serialize, StonehmSchema)]
-/// struct User {
-///     id: u32,
-///     name: String,
-///     email: String,
-///     is_active: bool,
-///     age: Option<u32>,
-/// }
-/// 
-/// // Generates JSON schema automatically
-/// let schema = User::schema();
-/// ```
-/// 
-/// ## Request/Response Types
-/// 
-/// ```rust
-/// # use serde::{Serialize, Deserialize};
-/// # use stonehm_macros::StonehmSchema;
-/// 
-/// #[derive(Deserialize, StonehmSchema)]
-/// struct CreateUserRequest {
-///     name: String,
-///     email: String,
-///     preferences: UserPreferences,
-/// }
-/// 
-/// #[derive(Serialize, StonehmSchema)]
-/// struct UserResponse {
-///     id: u32,
-///     name: String,
-///     email: String,
-///     created_at: String,
-/// }
-/// 
-/// #[derive(Serialize, Deserialize, StonehmSchema)]
-/// struct UserPreferences {
-///     newsletter: bool,
-///     theme: String,
-/// }
-/// ```
-/// 
-/// ## Error Types
-/// 
-/// ```rust
-/// # use serde::Serialize;
-/// # use stonehm_macros::StonehmSchema;
-/// 
-/// #[derive(Serialize, StonehmSchema)]
-/// enum ApiError {
-///     UserNotFound { id: u32 },
-///     ValidationError { field: String, message: String },
-///     DatabaseError,
-///     NetworkTimeout,
-/// }
-/// ```
-/// 
-/// # Generated Schema Format
-/// 
-/// The macro generates JSON schemas following the OpenAPI 3.0 specification:
-/// 
-/// ```json
-/// {
-///   "title": "User",
-///   "type": "object",
-///   "properties": {
-///     "id": { "type": "integer" },
-///     "name": { "type": "string" },
-///     "email": { "type": "string" },
-///     "is_active": { "type": "boolean" },
-///     "age": { "type": "integer" }
-///   },
-///   "required": ["id", "name", "email", "is_active"]
-/// }
-/// ```
-/// 
-/// # Usage with API Handlers
-/// 
-/// Use `StonehmSchema` types in your API handlers for automatic documentation:
-/// 
-/// ```rust,no_run
-/// # use axum::Json;
-/// # use stonehm::api_handler;
-/// # use stonehm_macros::StonehmSchema;
-/// # use serde::{Serialize, Deserialize};
-/// # #[derive(Deserialize, StoneSchema)] struct CreateUserRequest { name: String }
-/// # #[derive(Serialize, StoneSchema)] struct User { id: u32, name: String }
-/// # #[derive(Serialize, StoneSchema)] enum ApiError { NotFound }
-/// # use axum::response::IntoResponse;
-/// # impl IntoResponse for ApiError { fn into_response(self) -> axum::response::Response { todo!() } }
-/// 
-/// /// Create a new user
-/// #[api_handler]
-/// async fn create_user(
-///     Json(request): Json<CreateUserRequest>  // Schema automatically included
-/// ) -> Result<Json<User>, ApiError> {         // Both schemas automatically included
-///     // Implementation
-/// #   Ok(Json(User { id: 1, name: request.name }))
-/// }
-/// ```
-/// 
-/// # Requirements
-/// 
-/// - Your type must implement `Serialize` (for response types) or `Deserialize` (for request types)
-/// - The type must be used in a function signature annotated with `#[api_handler]`
-/// - For error types used in `Result<T, E>`, implement `axum::response::IntoResponse`
-#[proc_macro_derive(StonehmSchema)]
-pub fn derive_stone_schema(input: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(input as DeriveInput);
-    let name = &input.ident;
-    let name_str = name.to_string();
-    
-    // Generate a simple JSON schema string
-    let schema_json = match &input.data {
-        Data::Struct(data_struct) => {
-            match &data_struct.fields {
-                Fields::Named(fields) => {
-                    let mut properties = Vec::new();
-                    let mut required = Vec::new();
-                    
-                    for field in fields.named.iter() {
-                        if let Some(field_name) = &field.ident {
-                            let field_name_str = field_name.to_string();
-                            
-                            // Simple type mapping - extend as needed
-                            let type_str = match &field.ty {
-                                Type::Path(type_path) => {
-                                    if let Some(segment) = type_path.path.segments.last() {
-                                        match segment.ident.to_string().as_str() {
-                                            "String" | "str" => "string",
-                                            "i32" | "i64" | "u32" | "u64" | "isize" | "usize" => "integer",
-                                            "f32" | "f64" => "number",
-                                            "bool" => "boolean",
-                                            "Option" => {
-                                                // Skip required for Option types
-                                                "string" // simplified - extract inner type later
-                                            },
-                                            _ => "object", // custom types
-                                        }
-                                    } else {
-                                        "string"
-                                    }
-                                },
-                                _ => "string", // default for complex types
-                            };
-                            
-                            properties.push(format!("\"{field_name_str}\":{{\"type\":\"{type_str}\"}}"));
-                            
-                            // Only add to required if not an Option type
-                            if let Type::Path(type_path) = &field.ty {
-                                if let Some(segment) = type_path.path.segments.last() {
-                                    if segment.ident != "Option" {
-                                        required.push(format!("\"{field_name_str}\""));
-                                    }
-                                }
-                            } else {
-                                required.push(format!("\"{field_name_str}\""));
-                            }
-                        }
-                    }
-                    
-                    let properties_str = properties.join(",");
-                    let required_str = if required.is_empty() {
-                        String::new()
-                    } else {
-                        format!(",\"required\":[{}]", required.join(","))
-                    };
-                    
-                    format!("{{\"type\":\"object\",\"properties\":{{{properties_str}}}{required_str}}}")
-                },
-                _ => {
-                    "{\"type\":\"object\"}".to_string()
+/// Check whether a `#[stone(...)]` attribute contains the given flag, e.g. `#[stone(deprecated)]`
+fn has_stone_flag(attrs: &[Attribute], flag: &str) -> bool {
+    let mut found = false;
+    for attr in attrs {
+        if attr.path().is_ident("stone") {
+            let _ = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident(flag) {
+                    found = true;
+                } else {
+                    skip_stone_arg(&meta)?;
                 }
+                Ok(())
+            });
+        }
+    }
+    found
+}
+
+/// Check whether a `#[serde(...)]` attribute contains the given flag, e.g. `#[serde(flatten)]`
+fn has_serde_flag(attrs: &[Attribute], flag: &str) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+        .filter_map(|attr| attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated).ok())
+        .flatten()
+        .any(|meta| matches!(meta, Meta::Path(path) if path.is_ident(flag)))
+}
+
+/// Whether `#[serde(default)]` or `#[serde(default = "path")]` is present
+fn has_serde_default(attrs: &[Attribute]) -> bool {
+    has_serde_flag(attrs, "default") || serde_value(attrs, "default").is_some()
+}
+
+/// Value of a `#[serde(key = "...")]` attribute, e.g. `#[serde(rename_all = "lowercase")]`
+fn serde_value(attrs: &[Attribute], key: &str) -> Option<String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+        .filter_map(|attr| attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated).ok())
+        .flatten()
+        .find_map(|meta| match meta {
+            Meta::NameValue(meta) if meta.path.is_ident(key) => match meta.value {
+                Expr::Lit(ExprLit { lit: Lit::Str(s), .. }) => Some(s.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+}
+
+/// Serialized name of an enum variant under serde's `rename_all` rules
+fn rename_variant(name: &str, rule: &str) -> String {
+    let mut words = Vec::new();
+    for (index, c) in name.char_indices() {
+        if c.is_uppercase() && index > 0 {
+            words.push(String::new());
+        }
+        match words.last_mut() {
+            Some(word) => word.push(c),
+            None => words.push(c.to_string()),
+        }
+    }
+    match rule {
+        "lowercase" => name.to_lowercase(),
+        "UPPERCASE" => name.to_uppercase(),
+        "snake_case" => words.join("_").to_lowercase(),
+        "SCREAMING_SNAKE_CASE" => words.join("_").to_uppercase(),
+        "kebab-case" => words.join("-").to_lowercase(),
+        "SCREAMING-KEBAB-CASE" => words.join("-").to_uppercase(),
+        "camelCase" => name[..1].to_lowercase() + &name[1..],
+        _ => name.to_string(),
+    }
+}
+
+/// Serialized name of a snake_case struct field under serde's `rename_all` rules
+fn rename_field(name: &str, rule: &str) -> String {
+    let capitalize = |word: &str| {
+        let mut chars = word.chars();
+        chars.next().map(|first| first.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+    };
+    match rule {
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => name.to_uppercase(),
+        "PascalCase" => name.split('_').map(capitalize).collect(),
+        "camelCase" => {
+            let pascal: String = name.split('_').map(capitalize).collect();
+            rename_variant(&pascal, "camelCase")
+        },
+        "kebab-case" => name.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => name.replace('_', "-").to_uppercase(),
+        _ => name.to_string(),
+    }
+}
+
+/// Name of the component schema a field refers to, for plain (non-generic) user-defined types
+fn nested_schema_name(ty: &Type) -> Option<String> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    if well_known_schema(ty).is_some() {
+        return None;
+    }
+    let segment = type_path.path.segments.last()?;
+    let name = segment.ident.to_string();
+    let builtin = matches!(
+        name.as_str(),
+        "String" | "str" | "bool" | "char" | "i8" | "i16" | "i32" | "i64" | "i128" | "isize"
+            | "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "f32" | "f64" | "Value"
+    );
+    if builtin || !matches!(segment.arguments, PathArguments::None) {
+        return None;
+    }
+    Some(name)
+}
+
+/// String schema with a `format` for well-known library types, matched by their last path
+/// segment so any import path works
+fn well_known_schema(ty: &Type) -> Option<&'static str> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let schema = match type_path.path.segments.last()?.ident.to_string().as_str() {
+        "DateTime" | "NaiveDateTime" | "OffsetDateTime" => r#"{"type":"string","format":"date-time"}"#,
+        "NaiveDate" => r#"{"type":"string","format":"date"}"#,
+        "Uuid" => r#"{"type":"string","format":"uuid"}"#,
+        "Url" | "Uri" => r#"{"type":"string","format":"uri"}"#,
+        "Ipv4Addr" => r#"{"type":"string","format":"ipv4"}"#,
+        "Ipv6Addr" => r#"{"type":"string","format":"ipv6"}"#,
+        "IpAddr" => r#"{"type":"string","anyOf":[{"format":"ipv4"},{"format":"ipv6"}]}"#,
+        _ => return None,
+    };
+    Some(schema)
+}
+
+/// Whether a field holds text: `String`, `&str`, `Cow<str>` or `Box<str>`
+fn is_string_like(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(reference) => is_string_like(&reference.elem),
+        Type::Path(type_path) => {
+            let Some(segment) = type_path.path.segments.last() else {
+                return false;
+            };
+            match segment.ident.to_string().as_str() {
+                "String" | "str" => true,
+                "Cow" | "Box" | "Rc" | "Arc" => match &segment.arguments {
+                    PathArguments::AngleBracketed(args) => args.args.iter().any(|arg| {
+                        matches!(arg, GenericArgument::Type(inner) if is_string_like(inner))
+                    }),
+                    _ => false,
+                },
+                _ => false,
             }
         },
-        _ => {
-            "{\"type\":\"string\"}".to_string()
-        }
+        _ => false,
+    }
+}
+
+/// The single generic argument of a wrapper like `Option<T>` or `Vec<T>`, if `ty` is one of `wrappers`
+fn wrapped_type<'a>(ty: &'a Type, wrappers: &[&str]) -> Option<&'a Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if !wrappers.iter().any(|wrapper| segment.ident == wrapper) {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(inner) => Some(inner),
+        _ => None,
+    }
+}
+
+/// Value type of a map keyed by strings, e.g. `T` in `HashMap<String, T>`; other keys serialize
+/// as something other than a JSON object key, so they are not treated as maps
+fn map_value_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if !["HashMap", "BTreeMap", "IndexMap"].iter().any(|map| segment.ident == map) {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match (args.args.first()?, args.args.iter().nth(1)?) {
+        (GenericArgument::Type(key), GenericArgument::Type(value)) if is_string_like(key) => Some(value),
+        _ => None,
+    }
+}
+
+/// Schema for a field type, recursing through `Option` (nullable) and `Vec` (array) so that
+/// `Option<Vec<T>>` and `Vec<Option<T>>` keep their nesting order
+fn type_schema(ty: &Type) -> String {
+    if let Some(inner) = wrapped_type(ty, &["Option"]) {
+        let schema = type_schema(inner);
+        // OpenAPI 3.0 ignores `$ref` siblings, so a nullable reference goes through allOf
+        return if schema.starts_with("{\"$ref\"") {
+            format!("{{\"allOf\":[{schema}],\"nullable\":true}}")
+        } else {
+            extend_schema(&schema, ",\"nullable\":true")
+        };
+    }
+    if let Some(inner) = wrapped_type(ty, &["Vec", "VecDeque", "HashSet", "BTreeSet"]) {
+        return format!("{{\"type\":\"array\",\"items\":{}}}", type_schema(inner));
+    }
+    if let Some(value) = map_value_type(ty) {
+        return format!("{{\"type\":\"object\",\"additionalProperties\":{}}}", type_schema(value));
+    }
+    if is_string_like(ty) {
+        return "{\"type\":\"string\"}".to_string();
+    }
+    if let Some(schema) = well_known_schema(ty) {
+        return schema.to_string();
+    }
+    if is_json_value(ty) {
+        return "{}".to_string();
+    }
+    if let Some(type_name) = nested_schema_name(ty) {
+        return format!("{{\"$ref\":\"#/components/schemas/{type_name}\"}}");
+    }
+    format!("{{\"type\":\"{}\"{}}}", scalar_type(ty), numeric_format(ty))
+}
+
+/// Append `,"key":value` members to a schema object, including the empty schema `{}`
+fn extend_schema(schema: &str, members: &str) -> String {
+    if members.is_empty() {
+        schema.to_string()
+    } else if schema == "{}" {
+        format!("{{{}}}", members.trim_start_matches(','))
+    } else {
+        format!("{}{members}}}", &schema[..schema.len() - 1])
+    }
+}
+
+/// OpenAPI `format` of a sized number type, plus `minimum: 0` for unsigned integers
+fn numeric_format(ty: &Type) -> &'static str {
+    let Type::Path(type_path) = ty else {
+        return "";
     };
+    match type_path.path.segments.last().map(|segment| segment.ident.to_string()).as_deref() {
+        Some("i8" | "i16" | "i32") => ",\"format\":\"int32\"",
+        Some("u8" | "u16" | "u32") => ",\"format\":\"int32\",\"minimum\":0",
+        Some("i64" | "isize") => ",\"format\":\"int64\"",
+        Some("u64" | "usize") => ",\"format\":\"int64\",\"minimum\":0",
+        Some("u128") => ",\"minimum\":0",
+        Some("f32") => ",\"format\":\"float\"",
+        Some("f64") => ",\"format\":\"double\"",
+        _ => "",
+    }
+}
+
+/// Combine a number's format with its `#[stone(...)]` bounds; an explicit exclusive minimum
+/// replaces the implicit `minimum: 0` of unsigned types
+fn numeric_constraints(format: &str, bounds: &str) -> String {
+    if bounds.contains("\"minimum\"") {
+        format!("{}{bounds}", format.replace(",\"minimum\":0", ""))
+    } else {
+        format!("{format}{bounds}")
+    }
+}
+
+/// JSON schema type of a scalar field; unknown path types map to `"object"`
+fn scalar_type(ty: &Type) -> &'static str {
+    if is_string_like(ty) {
+        return "string";
+    }
+    match ty {
+        Type::Path(type_path) => match type_path.path.segments.last().map(|segment| segment.ident.to_string()).as_deref() {
+            Some("String" | "str" | "char") => "string",
+            Some("i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128" | "usize") => "integer",
+            Some("f32" | "f64") => "number",
+            Some("bool") => "boolean",
+            Some(_) => "object",
+            None => "string",
+        },
+        _ => "string",
+    }
+}
+
+/// Whether a field is `serde_json::Value` (matched by its last path segment)
+fn is_json_value(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path.path.segments.last().is_some_and(|segment| segment.ident == "Value"))
+}
+
+/// Join a field's `///` doc comment lines into one description
+fn doc_comment_text(attrs: &[Attribute]) -> Option<String> {
+    let mut lines = Vec::new();
+    for attr in attrs {
+        if let Meta::NameValue(meta) = &attr.meta {
+            if attr.path().is_ident("doc") {
+                if let Expr::Lit(ExprLit { lit: Lit::Str(s), .. }) = &meta.value {
+                    let line = s.value();
+                    if !line.trim().is_empty() {
+                        lines.push(line.trim().to_string());
+                    }
+                }
+            }
+        }
+    }
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join(" "))
+    }
+}
+
+/// Read a numeric `#[stone(key = value)]` argument, e.g. `exclusive_minimum = 0`
+fn stone_number(attrs: &[Attribute], key: &str) -> Option<String> {
+    let mut found = None;
+    for attr in attrs {
+        if attr.path().is_ident("stone") {
+            let _ = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident(key) {
+                    let value: Expr = meta.value()?.parse()?;
+                    let number = quote!(#value).to_string().replace(' ', "");
+                    if number.parse::<f64>().is_ok() {
+                        found = Some(number);
+                    }
+                } else {
+                    skip_stone_arg(&meta)?;
+                }
+                Ok(())
+            });
+        }
+    }
+    found
+}
+
+/// Validation keywords from `#[stone(minimum = 0, max_length = 255, pattern = "...", enum = [...])]`
+fn validation_keywords(attrs: &[Attribute]) -> String {
+    let mut keywords = String::new();
+    for (key, keyword) in [
+        ("minimum", "minimum"),
+        ("maximum", "maximum"),
+        ("min_length", "minLength"),
+        ("max_length", "maxLength"),
+    ] {
+        if let Some(number) = stone_number(attrs, key) {
+            keywords.push_str(&format!(",\"{keyword}\":{number}"));
+        }
+    }
+    for attr in attrs {
+        if attr.path().is_ident("stone") {
+            let _ = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("pattern") {
+                    let pattern: syn::LitStr = meta.value()?.parse()?;
+                    keywords.push_str(&format!(",\"pattern\":{}", serde_json::Value::String(pattern.value())));
+                } else if meta.path.is_ident("enum") {
+                    let values: syn::ExprArray = meta.value()?.parse()?;
+                    let values: Vec<serde_json::Value> = values.elems.iter().filter_map(literal_json).collect();
+                    keywords.push_str(&format!(",\"enum\":{}", serde_json::Value::Array(values)));
+                } else {
+                    skip_stone_arg(&meta)?;
+                }
+                Ok(())
+            });
+        }
+    }
+    keywords
+}
+
+/// JSON value of a string, number or boolean literal, e.g. an `enum = [...]` entry
+fn literal_json(expr: &Expr) -> Option<serde_json::Value> {
+    match expr {
+        Expr::Lit(ExprLit { lit: Lit::Str(s), .. }) => Some(serde_json::Value::String(s.value())),
+        Expr::Lit(ExprLit { lit: Lit::Bool(b), .. }) => Some(serde_json::Value::Bool(b.value)),
+        _ => serde_json::from_str(&quote!(#expr).to_string().replace(' ', "")).ok(),
+    }
+}
+
+/// Consume the value of a `#[stone(...)]` argument that isn't being looked for, such as
+/// `exclusive_minimum = 0` or `pattern_properties(...)`
+fn skip_stone_arg(meta: &syn::meta::ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(Token![=]) {
+        let _: Expr = meta.value()?.parse()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.parse_nested_meta(|inner| skip_stone_arg(&inner))?;
+    }
+    Ok(())
+}
+
+/// Read `#[stone(pattern_properties(pattern = "^[a-z]{2}$", type = "string"))]` as
+/// the key pattern and value type
+fn stone_pattern_properties(attrs: &[Attribute]) -> Option<(String, String)> {
+    let mut found = None;
+    for attr in attrs {
+        if attr.path().is_ident("stone") {
+            let _ = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("pattern_properties") {
+                    let mut pattern = None;
+                    let mut value_type = "string".to_string();
+                    meta.parse_nested_meta(|inner| {
+                        let value: syn::LitStr = inner.value()?.parse()?;
+                        if inner.path.is_ident("pattern") {
+                            pattern = Some(value.value());
+                        } else if inner.path.is_ident("type") {
+                            value_type = value.value();
+                        }
+                        Ok(())
+                    })?;
+                    found = pattern.map(|pattern| (pattern, value_type));
+                } else {
+                    skip_stone_arg(&meta)?;
+                }
+                Ok(())
+            });
+        }
+    }
+    found
+}
+
+/// Exclusive numeric bounds in the OpenAPI 3.0 form: a bound plus a boolean flag
+fn exclusive_bounds(attrs: &[Attribute]) -> String {
+    let mut bounds = String::new();
+    if let Some(minimum) = stone_number(attrs, "exclusive_minimum") {
+        bounds.push_str(&format!(",\"minimum\":{minimum},\"exclusiveMinimum\":true"));
+    }
+    if let Some(maximum) = stone_number(attrs, "exclusive_maximum") {
+        bounds.push_str(&format!(",\"maximum\":{maximum},\"exclusiveMaximum\":true"));
+    }
+    bounds
+}
+
+/// Object schema for named fields, with properties named as serde serializes them;
+/// `all_default` marks a container-level `#[serde(default)]`
+fn named_fields_schema(fields: &syn::FieldsNamed, rename_all: &str, all_default: bool) -> String {
+    let mut properties = Vec::new();
+    let mut required = Vec::new();
+    let mut flattened = Vec::new();
+    let mut additional_properties = "";
+
+    for field in fields.named.iter() {
+        // Skipped fields never appear on the wire, in responses or request bodies
+        if ["skip", "skip_serializing", "skip_deserializing"]
+            .iter()
+            .any(|flag| has_serde_flag(&field.attrs, flag))
+        {
+            continue;
+        }
+
+        // Flattened fields serialize inline: named types are merged through allOf,
+        // anything else (e.g. a map) allows extra properties
+        if has_serde_flag(&field.attrs, "flatten") {
+            match nested_schema_name(&field.ty) {
+                Some(type_name) => flattened.push(format!("{{\"$ref\":\"#/components/schemas/{type_name}\"}}")),
+                None => additional_properties = ",\"additionalProperties\":true",
+            }
+            continue;
+        }
+
+        if let Some(field_name) = &field.ident {
+            // Properties are named as serde serializes them; a field's own rename wins
+            let field_name_str = serde_value(&field.attrs, "rename")
+                .unwrap_or_else(|| rename_field(&field_name.to_string(), rename_all));
+
+            let mut markers = if has_stone_flag(&field.attrs, "deprecated") {
+                ",\"deprecated\":true".to_string()
+            } else {
+                String::new()
+            };
+            // Server-generated fields such as ids should not be sent in requests
+            if has_stone_flag(&field.attrs, "server_generated") {
+                markers.push_str(",\"readOnly\":true");
+            }
+
+            // The field's doc comment describes its property
+            if let Some(description) = doc_comment_text(&field.attrs) {
+                markers.insert_str(0, &format!(",\"description\":{}", serde_json::Value::String(description)));
+            }
+
+            let property = if let Some(type_name) = nested_schema_name(&field.ty) {
+                // OpenAPI 3.0 ignores siblings of `$ref`, so wrap it in allOf to keep them
+                let schema_ref = format!("{{\"$ref\":\"#/components/schemas/{type_name}\"}}");
+                if markers.is_empty() {
+                    schema_ref
+                } else {
+                    format!("{{\"allOf\":[{schema_ref}]{markers}}}")
+                }
+            } else if let Some((pattern, value_type)) = stone_pattern_properties(&field.attrs) {
+                // OpenAPI 3.0 has no patternProperties: values are typed through
+                // additionalProperties and the key pattern is kept as an extension
+                let value_schema = serde_json::json!({ "type": value_type });
+                let pattern_properties = serde_json::json!({ pattern: value_schema.clone() });
+                format!(
+                    "{{\"type\":\"object\",\"additionalProperties\":{value_schema},\"x-patternProperties\":{pattern_properties}{markers}}}"
+                )
+            } else if let Some(schema) = well_known_schema(&field.ty) {
+                extend_schema(schema, &markers)
+            } else if wrapped_type(&field.ty, &["Option", "Vec", "VecDeque", "HashSet", "BTreeSet"]).is_some() || map_value_type(&field.ty).is_some() {
+                // Optional, collection and map fields keep their nesting, e.g. a nullable
+                // array versus an array of nullable items
+                let mut schema = type_schema(&field.ty);
+                // Bounds and validation on an optional value still apply to the value
+                let bounds = match wrapped_type(&field.ty, &["Option"]) {
+                    Some(inner) if matches!(scalar_type(inner), "integer" | "number") => {
+                        format!("{}{}", exclusive_bounds(&field.attrs), validation_keywords(&field.attrs))
+                    }
+                    Some(inner) if scalar_type(inner) != "object" => validation_keywords(&field.attrs),
+                    _ => String::new(),
+                };
+                if bounds.contains("\"minimum\"") {
+                    schema = schema.replacen(",\"minimum\":0", "", 1);
+                }
+                extend_schema(&schema, &format!("{bounds}{markers}"))
+            } else if is_json_value(&field.ty) {
+                // Arbitrary JSON: an empty schema places no constraints (OpenAPI 3.0)
+                extend_schema("{}", &markers)
+            } else {
+                let type_str = scalar_type(&field.ty);
+                let constraints = if matches!(type_str, "integer" | "number") {
+                    let bounds = format!("{}{}", exclusive_bounds(&field.attrs), validation_keywords(&field.attrs));
+                    numeric_constraints(numeric_format(&field.ty), &bounds)
+                } else {
+                    validation_keywords(&field.attrs)
+                };
+                format!("{{\"type\":\"{type_str}\"{constraints}{markers}}}")
+            };
+
+            properties.push(format!("\"{field_name_str}\":{property}"));
+
+            // Only add to required if not an Option type and serde cannot default it
+            if all_default || has_serde_default(&field.attrs) {
+                continue;
+            }
+            if let Type::Path(type_path) = &field.ty {
+                if let Some(segment) = type_path.path.segments.last() {
+                    if segment.ident != "Option" {
+                        required.push(format!("\"{field_name_str}\""));
+                    }
+                }
+            } else {
+                required.push(format!("\"{field_name_str}\""));
+            }
+        }
+    }
+
+    let properties_str = properties.join(",");
+    let required_str = if required.is_empty() {
+        String::new()
+    } else {
+        format!(",\"required\":[{}]", required.join(","))
+    };
+
+    let own = format!("{{\"type\":\"object\",\"properties\":{{{properties_str}}}{required_str}{additional_properties}}}");
+    if flattened.is_empty() {
+        own
+    } else {
+        format!("{{\"allOf\":[{},{own}]}}", flattened.join(","))
+    }
+}
+
+/// Schema for an enum in serde's representation: unit-only enums are string enums, others a
+/// `oneOf` of their variants, with a discriminator when serde tags them with a property
+fn enum_schema(data_enum: &syn::DataEnum, attrs: &[Attribute]) -> String {
+    let rename_all = serde_value(attrs, "rename_all").unwrap_or_default();
+    let tag = serde_value(attrs, "tag");
+    let content = serde_value(attrs, "content");
+    let untagged = has_serde_flag(attrs, "untagged");
+    let name_of = |variant: &syn::Variant| {
+        serde_value(&variant.attrs, "rename").unwrap_or_else(|| rename_variant(&variant.ident.to_string(), &rename_all))
+    };
+    
+    let all_unit = data_enum.variants.iter().all(|variant| matches!(variant.fields, Fields::Unit));
+    if all_unit && tag.is_none() && !untagged {
+        let values: Vec<String> = data_enum.variants.iter().map(|variant| format!("\"{}\"", name_of(variant))).collect();
+        return format!("{{\"type\":\"string\",\"enum\":[{}]}}", values.join(","));
+    }
+    
+    let variants: Vec<serde_json::Value> = data_enum
+        .variants
+        .iter()
+        .map(|variant| {
+            let name = name_of(variant);
+            let body = variant_body(variant);
+            // Untagged unit variants serialize as null
+            if untagged {
+                return body.unwrap_or_else(|| serde_json::json!({"nullable": true}));
+            }
+            let tag_schema = serde_json::json!({"type": "string", "enum": [name]});
+            match (&tag, &content, body) {
+                (Some(tag), Some(content), Some(body)) => serde_json::json!({
+                    "type": "object",
+                    "properties": {tag.as_str(): tag_schema, content.as_str(): body},
+                    "required": [tag, content],
+                }),
+                (Some(tag), _, None) => serde_json::json!({
+                    "type": "object",
+                    "properties": {tag.as_str(): tag_schema},
+                    "required": [tag],
+                }),
+                // Internally tagged variants carry the tag next to their own fields
+                (Some(tag), None, Some(mut body)) => match body.get_mut("properties").and_then(|p| p.as_object_mut()) {
+                    Some(properties) => {
+                        properties.insert(tag.clone(), tag_schema);
+                        let mut required = vec![serde_json::json!(tag)];
+                        required.extend(body["required"].as_array().cloned().unwrap_or_default());
+                        body["required"] = serde_json::Value::Array(required);
+                        body
+                    },
+                    None => serde_json::json!({"allOf": [
+                        {"type": "object", "properties": {tag.as_str(): tag_schema}, "required": [tag]},
+                        body,
+                    ]}),
+                },
+                (None, _, None) => tag_schema,
+                (None, _, Some(body)) => serde_json::json!({
+                    "type": "object",
+                    "properties": {name.as_str(): body},
+                    "required": [name],
+                }),
+            }
+        })
+        .collect();
+    
+    let mut schema = serde_json::json!({"oneOf": variants});
+    if let (Some(tag), false) = (tag, untagged) {
+        schema["discriminator"] = serde_json::json!({"propertyName": tag});
+    }
+    schema.to_string()
+}
+
+/// Schema of a variant's data, `None` for unit variants; tuple variants are fixed-length arrays
+fn variant_body(variant: &syn::Variant) -> Option<serde_json::Value> {
+    match &variant.fields {
+        Fields::Named(fields) => {
+            let rename_all = serde_value(&variant.attrs, "rename_all").unwrap_or_default();
+            serde_json::from_str(&named_fields_schema(fields, &rename_all, false)).ok()
+        },
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => serde_json::from_str(&type_schema(&fields.unnamed[0].ty)).ok(),
+        Fields::Unnamed(fields) => Some(serde_json::json!({
+            "type": "array",
+            "minItems": fields.unnamed.len(),
+            "maxItems": fields.unnamed.len(),
+        })),
+        Fields::Unit => None,
+    }
+}
+
+/// Generate the JSON schema string for a type deriving `StonehmSchema`
+fn generate_schema_json(input: &DeriveInput) -> String {
+    // Generate a simple JSON schema string
+    let schema_json = match &input.data {
+        Data::Struct(data_struct) => {
+            match &data_struct.fields {
+                Fields::Named(fields) => {
+                    let rename_all = serde_value(&input.attrs, "rename_all").unwrap_or_default();
+                    named_fields_schema(fields, &rename_all, has_serde_default(&input.attrs))
+                },
+                _ => {
+                    "{\"type\":\"object\"}".to_string()
+                }
+            }
+        },
+        Data::Enum(data_enum) => enum_schema(data_enum, &input.attrs),
+        _ => {
+            "{\"type\":\"string\"}".to_string()
+        }
+    };
+    
+    // Container-level #[stone(deprecated)] marks the whole schema deprecated
+    if has_stone_flag(&input.attrs, "deprecated") {
+        format!("{{\"deprecated\":true,{}", &schema_json[1..])
+    } else {
+        schema_json
+    }
+}
+
+/// Schema of an `api_error` response body, which carries the serialized variant under `error`
+fn api_error_schema(input: &DeriveInput) -> String {
+    let error_schema = match &input.data {
+        Data::Enum(data_enum) => serde_json::from_str(&enum_schema(data_enum, &input.attrs))
+            .unwrap_or_else(|_| serde_json::json!({"type": "object"})),
+        _ => serde_json::json!({"type": "object"}),
+    };
+    serde_json::json!({
+        "type": "object",
+        "properties": {"error": error_schema},
+        "required": ["error"],
+    })
+    .to_string()
+}
+
+/// Pattern matching any value of a variant: `Self::V { .. }`, `Self::V(..)` or `Self::V`
+fn variant_pattern(variant: &syn::Variant) -> proc_macro2::TokenStream {
+    let ident = &variant.ident;
+    match variant.fields {
+        Fields::Named(_) => quote! { Self::#ident { .. } },
+        Fields::Unnamed(_) => quote! { Self::#ident(..) },
+        Fields::Unit => quote! { Self::#ident },
+    }
+}
+
+/// Raw lines of the `# Parameters`, `# Responses`, `# Request Body`, `# Security` and
+/// `# Notes` doc sections of an `api_handler`
+struct DocSections {
+    parameters: Vec<String>,
+    responses: Vec<String>,
+    request_body: Vec<String>,
+    security: Vec<String>,
+    notes: Vec<String>,
+}
+
+/// Split doc comment lines into their sections; a heading of any other name ends a section
+fn doc_sections(doc_lines: &[String]) -> DocSections {
+    let mut parameters = Vec::new();
+    let mut responses = Vec::new();
+    let mut request_body = Vec::new();
+    let mut security = Vec::new();
+    let mut notes = Vec::new();
+    
+    let mut current_section = "";
+    for line in doc_lines {
+        if line.starts_with("# Parameters") {
+            current_section = "parameters";
+        } else if line.starts_with("# Notes") || line.starts_with("# Remarks") {
+            current_section = "notes";
+        } else if current_section == "notes" && !line.starts_with("#") {
+            notes.push(line.clone());
+        } else if line.starts_with("# Security") {
+            current_section = "security";
+        } else if let (Some(requirement), "security") = (line.strip_prefix("- "), current_section) {
+            security.push(requirement.to_string());
+        } else if line.starts_with("# Responses") {
+            current_section = "responses";  
+        } else if line.starts_with("# Request Body") {
+            current_section = "request_body";
+        } else if line.starts_with('#') {
+            // Any other heading ends the current section
+            current_section = "";
+        } else if line.starts_with("- ") && current_section == "parameters" {
+            parameters.push(line[2..].to_string());
+        } else if line.starts_with("- ") && current_section == "responses" {
+            let response_line = line[2..].to_string();
+            
+            // Handle both simple format "- 200: Success" and complex format "- 404:"
+            if response_line.contains(":") {
+                if let Some(colon_pos) = response_line.find(':') {
+                    let status_part = response_line[..colon_pos].trim();
+                    let desc_part = response_line[colon_pos + 1..].trim();
+                    
+                    if let Some(status_code) = parse_status_code(status_part).filter(|code| (100..=599).contains(code)) {
+                        if desc_part.is_empty() {
+                            // Complex format - will collect description from following lines
+                            responses.push(format!("{status_code}:"));
+                        } else {
+                            // Simple format, with status names normalized to numeric codes
+                            responses.push(format!("{status_code}: {desc_part}"));
+                        }
+                    } else {
+                        responses.push(response_line);
+                    }
+                } else {
+                    responses.push(response_line);
+                }
+            } else {
+                responses.push(response_line);
+            }
+        } else if current_section == "responses" && !line.starts_with("#") && !line.starts_with("- ") {
+            // Handle YAML-style continuation lines for complex responses
+            if line.trim().starts_with("description:") {
+                let desc = line.trim().strip_prefix("description:").unwrap_or("").trim();
+                // Update the last response entry with the description
+                if let Some(last_response) = responses.last_mut() {
+                    if last_response.ends_with(':') {
+                        let status_code = last_response.trim_end_matches(':');
+                        *last_response = format!("{status_code}: {desc}");
+                    }
+                }
+            }
+        } else if current_section == "request_body" && !line.starts_with("#") {
+            request_body.push(line.clone());
+        }
+    }
+    
+    DocSections { parameters, responses, request_body, security, notes }
+}
+
+/// Status code and description of an `api_error` variant from a doc comment like
+/// `/// 404: User not found`; variants without one map to 500, described by their name
+fn variant_status(variant: &syn::Variant) -> (u16, String) {
+    for attr in &variant.attrs {
+        if attr.path().is_ident("doc") {
+            if let Meta::NameValue(meta) = &attr.meta {
+                if let Expr::Lit(ExprLit { lit: Lit::Str(s), .. }) = &meta.value {
+                    let doc = s.value();
+                    // Look for pattern like "404: Description"
+                    if let Some((code_part, description)) = doc.split_once(':') {
+                        if let Some(code) = parse_status_code(code_part.trim()) {
+                            return (code, description.trim().to_string());
+                        }
+                    }
+                }
+            }
+        }
+    }
+    (500, variant.ident.to_string())
+}
+
+/// Derive macro for automatic JSON schema generation.
+/// 
+/// This derive macro automatically implements the `StonehmSchema` trait for your types,
+/// enabling automatic JSON schema generation for OpenAPI specifications. Use this
+/// on all request and response types that you want to appear in your OpenAPI spec.
+/// 
+/// # Type Support
+/// 
+/// Supported Rust types and their JSON schema mappings:
+/// - `String`, `&str` → `"string"`
+/// - `i32`, `i64`, `u32`, `u64`, etc. → `"integer"` with format `int32` or `int64`;
+///   unsigned integers also get `minimum: 0`
+/// - `f32`, `f64` → `"number"` with format `float` or `double`
+/// - `bool` → `"boolean"`
+/// - `Option<T>` → makes field optional and its schema `nullable`
+/// - `Vec<T>` → `"array"` with item schema; `Option<Vec<T>>` is a nullable array while
+///   `Vec<Option<T>>` is an array of nullable items
+/// - Nested structs → `$ref` to their component schema; a field doc comment is kept by
+///   wrapping the ref in `allOf`, since OpenAPI 3.0 ignores `$ref` siblings
+/// - Enums with only unit variants → `"string"` with their `enum` values, honouring
+///   `#[serde(rename_all = "...")]` and `#[serde(rename = "...")]`
+/// - Enums with data → `oneOf` of their variants in serde's representation; `#[serde(tag = "...")]`
+///   adds the tag property to each variant and a `discriminator`
+/// - `HashMap<String, T>` / `BTreeMap<String, T>` → `"object"` with `additionalProperties` typed
+///   like `T`; maps with non-string keys are plain objects
+/// - `DateTime`/`NaiveDateTime` → `"date-time"`, `NaiveDate` → `"date"`, `Uuid` → `"uuid"`,
+///   `Url` → `"uri"` and `Ipv4Addr`/`Ipv6Addr`/`IpAddr` → `"ipv4"`/`"ipv6"` string formats
+/// - `serde_json::Value` → `{}`, i.e. any JSON
+/// - `&str`, `Cow<str>` and `Box<str>` → `"string"`, like `String`
+/// - `#[serde(flatten)]` fields → `allOf` combining the flattened type's `$ref` with the
+///   struct's own properties; flattened maps allow `additionalProperties`
+/// - `#[serde(default)]` fields, or all fields of a `#[serde(default)]` struct → not required
+/// - `#[serde(skip)]`, `skip_serializing` and `skip_deserializing` fields → omitted
+/// 
+/// # Attributes
+/// 
+/// - `///` doc comments on fields become the `description` of their property
+/// - `#[serde(rename = "...")]` on a field names its property as serialized, taking precedence
+///   over a container-level `#[serde(rename_all = "camelCase")]` (or any other serde case rule)
+/// - `#[stone(deprecated)]` on a field sets `"deprecated": true` on its property schema
+/// - `#[stone(deprecated)]` on the type marks the whole schema deprecated
+/// - `#[stone(exclusive_minimum = 0)]` / `#[stone(exclusive_maximum = 100)]` on a numeric field
+///   set an exclusive bound (`minimum` plus `exclusiveMinimum: true`, as OpenAPI 3.0 expects)
+/// - `#[stone(minimum = 0, maximum = 120)]`, `#[stone(min_length = 1, max_length = 255)]`,
+///   `#[stone(pattern = "^[a-z]+$")]` and `#[stone(enum = ["a", "b"])]` on a field emit the
+///   matching validation keywords
+/// - `#[stone(pattern_properties(pattern = "^[a-z]{2}$", type = "string"))]` on a map field
+///   types its values through `additionalProperties`, keeping the key pattern as
+///   `x-patternProperties` since OpenAPI 3.0 has no `patternProperties`
+/// - `#[stone(server_generated)]` on a field like `id` or `created_at` sets `"readOnly": true`,
+///   so clients reusing the type as a request know not to send it
+/// 
+/// # Examples
+/// 
+/// ## Basic Struct
+/// 
+/// ```rust
+/// use serde::Serialize;
+/// use stonehm::StonehmSchema;
+/// 
+/// #[derive(Serialize, StonehmSchema)]
+/// struct User {
+///     id: u32,
+///     name: String,
+///     email: String,
+///     is_active: bool,
+///     age: Option<u32>,
+/// }
+/// 
+/// // Generates JSON schema automatically
+/// let schema = User::schema();
+/// ```
+/// 
+/// ## Request/Response Types
+/// 
+/// ```rust
+/// # use serde::{Serialize, Deserialize};
+/// # use stonehm_macros::StonehmSchema;
+/// 
+/// #[derive(Deserialize, StonehmSchema)]
+/// struct CreateUserRequest {
+///     name: String,
+///     email: String,
+///     preferences: UserPreferences,
+/// }
+/// 
+/// #[derive(Serialize, StonehmSchema)]
+/// struct UserResponse {
+///     id: u32,
+///     name: String,
+///     email: String,
+///     created_at: String,
+/// }
+/// 
+/// #[derive(Serialize, Deserialize, StonehmSchema)]
+/// struct UserPreferences {
+///     newsletter: bool,
+///     theme: String,
+/// }
+/// ```
+/// 
+/// ## Error Types
+/// 
+/// ```rust
+/// # use serde::Serialize;
+/// # use stonehm_macros::StonehmSchema;
+/// 
+/// #[derive(Serialize, StonehmSchema)]
+/// enum ApiError {
+///     UserNotFound { id: u32 },
+///     ValidationError { field: String, message: String },
+///     DatabaseError,
+///     NetworkTimeout,
+/// }
+/// ```
+/// 
+/// # Generated Schema Format
+/// 
+/// The macro generates JSON schemas following the OpenAPI 3.0 specification:
+/// 
+/// ```json
+/// {
+///   "title": "User",
+///   "type": "object",
+///   "properties": {
+///     "id": { "type": "integer", "format": "int32", "minimum": 0 },
+///     "name": { "type": "string" },
+///     "email": { "type": "string" },
+///     "is_active": { "type": "boolean" },
+///     "age": { "type": "integer", "format": "int32", "minimum": 0, "nullable": true }
+///   },
+///   "required": ["id", "name", "email", "is_active"]
+/// }
+/// ```
+/// 
+/// # Usage with API Handlers
+/// 
+/// Use `StonehmSchema` types in your API handlers for automatic documentation:
+/// 
+/// ```rust,no_run
+/// # use axum::Json;
+/// # use stonehm::api_handler;
+/// # use stonehm_macros::StonehmSchema;
+/// # use serde::{Serialize, Deserialize};
+/// # #[derive(Deserialize, StonehmSchema)] struct CreateUserRequest { name: String }
+/// # #[derive(Serialize, StonehmSchema)] struct User { id: u32, name: String }
+/// # #[derive(Serialize, StonehmSchema)] enum ApiError { NotFound }
+/// # use axum::response::IntoResponse;
+/// # impl IntoResponse for ApiError { fn into_response(self) -> axum::response::Response { todo!() } }
+/// 
+/// /// Create a new user
+/// #[api_handler]
+/// async fn create_user(
+///     Json(request): Json<CreateUserRequest>  // Schema automatically included
+/// ) -> Result<Json<User>, ApiError> {         // Both schemas automatically included
+///     // Implementation
+/// #   Ok(Json(User { id: 1, name: request.name }))
+/// }
+/// ```
+/// 
+/// # Requirements
+/// 
+/// - Your type must implement `Serialize` (for response types) or `Deserialize` (for request types)
+/// - The type must be used in a function signature annotated with `#[api_handler]`
+/// - For error types used in `Result<T, E>`, implement `axum::response::IntoResponse`
+#[proc_macro_derive(StonehmSchema, attributes(stone))]
+pub fn derive_stone_schema(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let name_str = name.to_string();
+    
+    let schema_json = generate_schema_json(&input);
+    // Borrowing DTOs like `struct User<'a>` carry their lifetimes through
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    
+    let expanded = quote! {
+        impl #impl_generics stonehm::StonehmSchema for #name #ty_generics #where_clause {
+            fn schema() -> String {
+                #schema_json.to_string()
+            }
+        }
+        
+        // Register this type's schema for OpenAPI components
+        stonehm::inventory::submit! {
+            stonehm::SchemaRegistration {
+                type_name: #name_str,
+                schema_json: #schema_json,
+            }
+        }
+    };
+    
+    TokenStream::from(expanded)
+}
+
+/// Attribute macro for automatically generating HTTP error responses.
+/// 
+/// This macro automatically implements `axum::response::IntoResponse` for error enums,
+/// mapping each variant to an appropriate HTTP status code. Use doc comments with
+/// `/// {code}: {description}` format to specify status codes for variants.
+/// 
+/// # Basic Usage
+/// 
+/// ```rust
+/// use stonehm_macros::api_error;
+/// 
+/// #[api_error]
+/// #[derive(serde::Serialize)]
+/// enum ApiError {
+///     /// 404: User not found
+///     UserNotFound { id: u32 },
+///     
+///     /// 400: Invalid input provided
+///     InvalidInput { message: String },
+///     
+///     /// 401: Authentication required
+///     Unauthorized,
+///     
+///     /// 403: Access forbidden
+///     Forbidden,
+///     
+///     // Variants without doc comments default to 500 Internal Server Error
+///     DatabaseError,
+///     NetworkTimeout,
+/// }
+/// ```
+/// 
+/// # Generated Implementation
+/// 
+/// The macro automatically generates:
+/// - `IntoResponse` implementation for HTTP responses
+/// - `Serialize` implementation for JSON serialization  
+/// - `StonehmSchema` implementation for OpenAPI documentation
+/// - Maps each variant to its specified status code
+/// - Uses 500 Internal Server Error for variants without doc comments
+/// - Serializes the error as JSON in the response body
+/// 
+/// # Supported Status Codes
+/// 
+/// Common HTTP status codes you can use:
+/// - 200 OK
+/// - 201 Created  
+/// - 204 No Content
+/// - 400 Bad Request
+/// - 401 Unauthorized
+/// - 403 Forbidden
+/// - 404 Not Found
+/// - 409 Conflict
+/// - 422 Unprocessable Entity
+/// - 500 Internal Server Error
+/// - 502 Bad Gateway
+/// - 503 Service Unavailable
+/// 
+/// # Examples
+/// 
+/// ## Basic Error Enum
+/// 
+/// ```rust
+/// # use stonehm_macros::api_error;
+/// # use serde::Serialize;
+/// #[api_error]
+/// #[derive(Serialize)]
+/// enum UserError {
+///     /// 404: User not found
+///     NotFound { id: u32 },
+///     
+///     /// 400: Invalid user data
+///     InvalidData { field: String, reason: String },
+/// }
+/// ```
+/// 
+/// ## With Custom Serialization
+/// 
+/// ```rust
+/// # use stonehm_macros::api_error;
+/// # use serde::Serialize;
+/// #[api_error]
+/// #[derive(Serialize)]
+/// #[serde(tag = "error", content = "details")]
+/// enum ApiError {
+///     /// 401: Missing or invalid authentication token
+///     #[serde(rename = "auth_required")]
+///     AuthRequired,
+///     
+///     /// 403: User lacks required permissions
+///     #[serde(rename = "access_denied")]
+///     AccessDenied { required_role: String },
+/// }
+/// ```
+/// 
+/// ## Usage in Handlers
+/// 
+/// ```rust,no_run
+/// # use axum::Json;
+/// # use stonehm_macros::{api_error, api_handler, StonehmSchema};
+/// # use serde::{Serialize, Deserialize};
+/// # #[derive(Deserialize, StonehmSchema)]
+/// # struct UpdateUserRequest { name: String }
+/// # #[derive(Serialize, StonehmSchema)]
+/// # struct User { id: u32, name: String }
+/// # #[api_error]
+/// # #[derive(Serialize)]
+/// # enum UserError {
+/// #     /// 404: User not found
+/// #     NotFound { id: u32 },
+/// #     /// 400: Invalid data
+/// #     InvalidData { message: String },
+/// # }
+/// 
+/// /// Update user information
+/// #[api_handler]
+/// async fn update_user(
+///     axum::extract::Path(id): axum::extract::Path<u32>,
+///     Json(data): Json<UpdateUserRequest>
+/// ) -> Result<Json<User>, UserError> {
+///     if id == 0 {
+///         return Err(UserError::NotFound { id });
+///     }
+///     
+///     if data.name.is_empty() {
+///         return Err(UserError::InvalidData { 
+///             message: "Name cannot be empty".to_string() 
+///         });
+///     }
+///     
+///     Ok(Json(User { id, name: data.name }))
+/// }
+/// ```
+/// 
+/// # Requirements
+/// 
+/// - The error enum must also have `#[derive(Serialize)]` or implement `Serialize` manually
+/// - Each variant's doc comment should start with a 3-digit HTTP status code followed by a colon
+/// - The macro will automatically implement `axum::response::IntoResponse`
+/// - The macro will register the error schema for OpenAPI documentation
+#[proc_macro_attribute]
+pub fn api_error(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    let name = &input.ident;
+    let name_str = name.to_string();
+    
+    // Extract status codes from doc comments
+    let mut variant_status_codes = Vec::new();
+    
+    if let Data::Enum(data_enum) = &input.data {
+        for variant in &data_enum.variants {
+            let (status_code, description) = variant_status(variant);
+            variant_status_codes.push((variant_pattern(variant), status_code, description));
+        }
+    }
+    
+    // Generate match arms for IntoResponse implementation
+    let match_arms = variant_status_codes.iter().map(|(pattern, status_code, _)| {
+        quote! {
+            #pattern => #status_code
+        }
+    });
+    let catalog_entries = variant_status_codes.iter().map(|(_, status_code, description)| {
+        quote! { (#status_code, #description) }
+    });
+    let schema_json = api_error_schema(&input);
+    
+    // Generate the implementation
+    let expanded = quote! {
+        #input
+        
+        impl axum::response::IntoResponse for #name {
+            fn into_response(self) -> axum::response::Response {
+                use axum::http::StatusCode;
+                
+                let status = match &self {
+                    #(#match_arms),*
+                };
+                
+                let body = axum::Json(serde_json::json!({
+                    "error": serde_json::to_value(&self).unwrap_or_else(|_| serde_json::json!({
+                        "message": "Failed to serialize error"
+                    }))
+                }));
+                
+                (StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR), body).into_response()
+            }
+        }
+        
+        // Also implement StonehmSchema for the error type
+        impl stonehm::StonehmSchema for #name {
+            fn schema() -> String {
+                #schema_json.to_string()
+            }
+        }
+        
+        // Register this error type's schema
+        stonehm::inventory::submit! {
+            stonehm::SchemaRegistration {
+                type_name: #name_str,
+                schema_json: #schema_json,
+            }
+        }
+        
+        // Register the status code and description of each variant
+        stonehm::inventory::submit! {
+            stonehm::ErrorRegistration {
+                type_name: #name_str,
+                variants: &[#(#catalog_entries),*],
+            }
+        }
+    };
+    
+    TokenStream::from(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+    
+    #[test]
+    fn test_extract_request_body_type() {
+        // Test Json<T> extraction
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            Json(body): Json<CreateUserRequest>
+        };
+        
+        let result = extract_request_body_type(&inputs);
+        assert_eq!(result, Some("CreateUserRequest".to_string()));
+        
+        // Test with multiple parameters
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            Path(id): Path<u32>,
+            Json(data): Json<UpdateRequest>
+        };
+        
+        let result = extract_request_body_type(&inputs);
+        assert_eq!(result, Some("UpdateRequest".to_string()));
+        
+        // Test without Json parameter
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            Path(id): Path<u32>
+        };
+        
+        let result = extract_request_body_type(&inputs);
+        assert_eq!(result, None);
+        
+        // Test with state and extension extractors before the body
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            State(state): State<AppState>,
+            Extension(user): Extension<CurrentUser>,
+            Json(request): Json<CreateUserRequest>
+        };
+        
+        let result = extract_request_body_type(&inputs);
+        assert_eq!(result, Some("CreateUserRequest".to_string()));
+        
+        // Test with an unpatterned body and a fully qualified Json
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            State(state): State<Json<Config>>,
+            payload: axum::Json<UpdateRequest>
+        };
+        
+        let result = extract_request_body_type(&inputs);
+        assert_eq!(result, Some("UpdateRequest".to_string()));
+        
+        // Test with state only
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            State(state): State<AppState>
+        };
+        
+        let result = extract_request_body_type(&inputs);
+        assert_eq!(result, None);
+    }
+    
+    #[test]
+    fn test_extract_response_and_error_types() {
+        // Test Result<Json<T>, E>
+        let output: ReturnType = parse_quote! {
+            -> Result<Json<UserResponse>, ApiError>
+        };
+        
+        let (response_type, error_type) = extract_response_and_error_types(&output);
+        assert_eq!(response_type, Some("UserResponse".to_string()));
+        assert_eq!(error_type, Some("ApiError".to_string()));
+        
+        // Test Json<T> without Result
+        let output: ReturnType = parse_quote! {
+            -> Json<HealthResponse>
+        };
+        
+        let (response_type, error_type) = extract_response_and_error_types(&output);
+        assert_eq!(response_type, Some("HealthResponse".to_string()));
+        assert_eq!(error_type, None);
+        
+        // Test Result with tuple success type
+        let output: ReturnType = parse_quote! {
+            -> Result<(StatusCode, Json<CreatedResponse>), CreateError>
+        };
+        
+        let (response_type, error_type) = extract_response_and_error_types(&output);
+        assert_eq!(response_type, Some("CreatedResponse".to_string()));
+        assert_eq!(error_type, Some("CreateError".to_string()));
+        
+        // Test tuple success type without Result
+        let output: ReturnType = parse_quote! {
+            -> (StatusCode, HeaderMap, Json<CreatedResponse>)
+        };
+        
+        let (response_type, error_type) = extract_response_and_error_types(&output);
+        assert_eq!(response_type, Some("CreatedResponse".to_string()));
+        assert_eq!(error_type, None);
+        
+        // Test no return type
+        let output: ReturnType = ReturnType::Default;
+        
+        let (response_type, error_type) = extract_response_and_error_types(&output);
+        assert_eq!(response_type, None);
+        assert_eq!(error_type, None);
+    }
+    
+    #[test]
+    fn test_body_success_status() {
+        let output: ReturnType = parse_quote!(-> Result<(StatusCode, Json<User>), ApiError>);
+        assert!(returns_status_code(&output));
+        assert!(returns_status_code(&parse_quote!(-> StatusCode)));
+        assert!(!returns_status_code(&parse_quote!(-> Result<Json<User>, ApiError>)));
+        
+        let block: syn::Block = parse_quote!({
+            let user = create(request).await?;
+            Ok((StatusCode::CREATED, Json(user)))
+        });
+        assert_eq!(body_success_status(&block), Some(201));
+        
+        // Error statuses are ignored; two different 2xx statuses are ambiguous
+        let block: syn::Block = parse_quote!({
+            if missing { return Err((StatusCode::NOT_FOUND, "missing")); }
+            Ok(axum::http::StatusCode::NO_CONTENT)
+        });
+        assert_eq!(body_success_status(&block), Some(204));
+        let block: syn::Block = parse_quote!({
+            if created { StatusCode::CREATED } else { StatusCode::OK }
+        });
+        assert_eq!(body_success_status(&block), None);
+    }
+    
+    #[test]
+    fn test_returns_event_stream() {
+        let output: ReturnType = parse_quote!(-> Sse<impl Stream<Item = Result<Event, Infallible>>>);
+        assert!(returns_event_stream(&output));
+        
+        let output: ReturnType = parse_quote!(-> Result<Sse<EventStream>, ApiError>);
+        assert!(returns_event_stream(&output));
+        
+        let output: ReturnType = parse_quote!(-> Json<User>);
+        assert!(!returns_event_stream(&output));
+        assert!(!returns_event_stream(&ReturnType::Default));
+    }
+    
+    #[test]
+    fn test_sanitize_type_for_identifier() {
+        assert_eq!(sanitize_type_for_identifier("Vec<String>"), "Vec_String_");
+        assert_eq!(sanitize_type_for_identifier("HashMap<String, Value>"), "HashMap_String_Value_");
+        assert_eq!(sanitize_type_for_identifier("Option<User>"), "Option_User_");
+        assert_eq!(sanitize_type_for_identifier("Result<T, E>"), "Result_T_E_");
+        assert_eq!(sanitize_type_for_identifier("&str"), "_str");
+        assert_eq!(sanitize_type_for_identifier("*const u8"), "_const_u8");
+    }
+    
+    #[test]
+    fn test_extract_docs_simple() {
+        let attrs = vec![
+            parse_quote!(#[doc = " Simple handler"]),
+            parse_quote!(#[doc = " "]),
+            parse_quote!(#[doc = " This is a simple test handler"]),
+        ];
+        
+        let docs = extract_docs(&attrs);
+        assert_eq!(docs.summary, Some("Simple handler".to_string()));
+        assert_eq!(docs.description, Some("This is a simple test handler".to_string()));
+    }
+    
+    #[test]
+    fn test_extract_docs_with_parameters() {
+        let attrs = vec![
+            parse_quote!(#[doc = " Get user by ID"]),
+            parse_quote!(#[doc = " "]),
+            parse_quote!(#[doc = " Retrieves user information"]),
+            parse_quote!(#[doc = " "]),
+            parse_quote!(#[doc = " # Parameters"]),
+            parse_quote!(#[doc = " - id (path): User ID [example: 42]"]),
+            parse_quote!(#[doc = " - include_deleted (query): Include deleted users"]),
+            parse_quote!(#[doc = " - page (query, u32, required): Page number"]),
+        ];
+        
+        let docs = extract_docs(&attrs);
+        assert_eq!(docs.summary, Some("Get user by ID".to_string()));
+        assert_eq!(docs.parameters.len(), 3);
+        assert_eq!(docs.parameters[0].name, "id");
+        assert_eq!(docs.parameters[0].param_type, "path");
+        assert_eq!(docs.parameters[0].description, "User ID");
+        assert_eq!(docs.parameters[0].example, Some("42".to_string()));
+        assert_eq!(docs.parameters[1].name, "include_deleted");
+        assert_eq!(docs.parameters[1].param_type, "query");
+        assert_eq!(docs.parameters[1].required, None);
+        assert_eq!(docs.parameters[2].param_type, "query");
+        assert_eq!(docs.parameters[2].required, Some(true));
+    }
+    
+    #[test]
+    fn test_extract_docs_with_request_body() {
+        let attrs = vec![
+            parse_quote!(#[doc = " Create user"]),
+            parse_quote!(#[doc = " "]),
+            parse_quote!(#[doc = " # Request Body"]),
+            parse_quote!(#[doc = " Content-Type: application/json"]),
+            parse_quote!(#[doc = " User data for creation"]),
+            parse_quote!(#[doc = " - name (string): Full name"]),
+            parse_quote!(#[doc = " - email (string): Email address"]),
+        ];
+        
+        let docs = extract_docs(&attrs);
+        assert!(docs.request_body.is_some());
+        
+        let body = docs.request_body.unwrap();
+        assert_eq!(body.content_type, "application/json");
+        assert_eq!(body.description, "User data for creation");
+        assert_eq!(body.fields.len(), 2);
+        assert_eq!(body.fields[0].name, "name");
+        assert_eq!(body.fields[0].field_type, "string");
+        assert_eq!(body.fields[0].description, "Full name");
+        assert_eq!(body.fields[1].name, "email");
+        assert_eq!(body.fields[1].description, "Email address");
+    }
+    
+    #[test]
+    fn test_extract_docs_with_responses() {
+        let attrs = vec![
+            parse_quote!(#[doc = " Delete user"]),
+            parse_quote!(#[doc = " "]),
+            parse_quote!(#[doc = " # Responses"]),
+            parse_quote!(#[doc = " - 204: User deleted"]),
+            parse_quote!(#[doc = " - 404: User not found"]),
+            parse_quote!(#[doc = " - 403: Access denied"]),
+        ];
+        
+        let docs = extract_docs(&attrs);
+        assert_eq!(docs.responses.len(), 3);
+        
+        assert_eq!(docs.responses[0].status_code, 204);
+        assert_eq!(docs.responses[0].description, "User deleted");
+        
+        assert_eq!(docs.responses[1].status_code, 404);
+        assert_eq!(docs.responses[1].description, "User not found");
+        
+        assert_eq!(docs.responses[2].status_code, 403);
+        assert_eq!(docs.responses[2].description, "Access denied");
+    }
+    
+    #[test]
+    fn test_extract_docs_complex_responses() {
+        let attrs = vec![
+            parse_quote!(#[doc = " Complex endpoint"]),
+            parse_quote!(#[doc = " "]),
+            parse_quote!(#[doc = " # Responses"]),
+            parse_quote!(#[doc = " - 200:"]),
+            parse_quote!(#[doc = "   description: Success"]),
+            parse_quote!(#[doc = "   content:"]),
+            parse_quote!(#[doc = "     application/json:"]),
+            parse_quote!(#[doc = "       schema: UserResponse"]),
+            parse_quote!(#[doc = " - 404:"]),
+            parse_quote!(#[doc = "   description: Not found"]),
+        ];
+        
+        let docs = extract_docs(&attrs);
+        assert_eq!(docs.responses.len(), 2);
+        
+        let resp200 = &docs.responses[0];
+        assert_eq!(resp200.status_code, 200);
+        assert_eq!(resp200.description, "Success");
+        assert!(resp200.content.is_some());
+        
+        let content = resp200.content.as_ref().unwrap();
+        assert_eq!(content.media_type, "application/json");
+        assert_eq!(content.schema, Some("UserResponse".to_string()));
+        
+        let resp404 = &docs.responses[1];
+        assert_eq!(resp404.status_code, 404);
+        assert_eq!(resp404.description, "Not found");
+    }
+    
+    #[test]
+    fn test_extract_docs_with_examples() {
+        let attrs = vec![
+            parse_quote!(#[doc = " Test endpoint"]),
+            parse_quote!(#[doc = " "]),
+            parse_quote!(#[doc = " # Responses"]),
+            parse_quote!(#[doc = " - 200:"]),
+            parse_quote!(#[doc = "   description: Success"]),
+            parse_quote!(#[doc = "   examples:"]),
+            parse_quote!(#[doc = "     - name: success_example"]),
+            parse_quote!(#[doc = "       summary: Successful response"]),
+            parse_quote!(#[doc = r#"       value: {"status": "ok"}"#]),
+        ];
+        
+        let docs = extract_docs(&attrs);
+        assert_eq!(docs.responses.len(), 1);
+        
+        let resp = &docs.responses[0];
+        assert!(resp.examples.is_some());
+        
+        let examples = resp.examples.as_ref().unwrap();
+        assert_eq!(examples.len(), 1);
+        
+        let example = &examples[0];
+        assert_eq!(example.name, "success_example");
+        assert_eq!(example.summary, Some("Successful response".to_string()));
+        assert_eq!(example.value, r#"{"status": "ok"}"#);
+    }
+    
+    #[test]
+    fn test_extract_docs_default_example() {
+        let attrs = vec![
+            parse_quote!(#[doc = " Create a user"]),
+            parse_quote!(#[doc = " # Responses"]),
+            parse_quote!(#[doc = " - 400:"]),
+            parse_quote!(#[doc = "   description: Invalid request"]),
+            parse_quote!(#[doc = "   examples:"]),
+            parse_quote!(#[doc = "     - name: missing_name"]),
+            parse_quote!(#[doc = r#"       value: {"error": "name is required"}"#]),
+            parse_quote!(#[doc = "     - name: bad_email"]),
+            parse_quote!(#[doc = "       default: true"]),
+            parse_quote!(#[doc = r#"       value: {"error": "invalid email"}"#]),
+        ];
+        
+        let docs = extract_docs(&attrs);
+        let examples = docs.responses[0].examples.as_ref().unwrap();
+        assert!(!examples[0].default);
+        assert!(examples[1].default);
+        
+        let json: serde_json::Value = serde_json::from_str(&response_examples_json(&docs.responses)).unwrap();
+        assert_eq!(json["400"]["bad_email"]["x-default"], true);
+        assert!(json["400"]["missing_name"].get("x-default").is_none());
+    }
+    
+    #[test]
+    fn test_extract_docs_empty() {
+        let attrs = vec![];
+        let docs = extract_docs(&attrs);
+        
+        assert_eq!(docs.summary, None);
+        assert_eq!(docs.description, None);
+        assert!(docs.parameters.is_empty());
+        assert!(docs.request_body.is_none());
+        assert!(docs.responses.is_empty());
+    }
+    
+    #[test]
+    fn test_stone_deprecated_field() {
+        let input: DeriveInput = parse_quote! {
+            struct User {
+                name: String,
+                #[stone(deprecated)]
+                nickname: String,
+            }
+        };
+        
+        let schema = generate_schema_json(&input);
+        assert!(schema.contains(r#""name":{"type":"string"}"#));
+        assert!(schema.contains(r#""nickname":{"type":"string","deprecated":true}"#));
+        assert!(schema.starts_with(r#"{"type":"object""#));
+    }
+    
+    #[test]
+    fn test_notes_section_ends_at_next_heading() {
+        let lines: Vec<String> = [
+            "Get a user",
+            "# Notes",
+            "Cached for a minute.",
+            "# Examples",
+            "curl /users/1",
+            "# Responses",
+            "- 200: User found",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+        
+        let sections = doc_sections(&lines);
+        assert_eq!(sections.notes, ["Cached for a minute."]);
+        assert_eq!(sections.responses, ["200: User found"]);
+        assert!(sections.parameters.is_empty() && sections.request_body.is_empty());
+    }
+    
+    #[test]
+    fn test_api_error_schema() {
+        let input: DeriveInput = parse_quote! {
+            #[serde(tag = "error", content = "details")]
+            enum ApiError {
+                /// 404: User not found
+                #[serde(rename = "user_not_found")]
+                UserNotFound { id: u32 },
+                /// 500: Internal server error
+                #[serde(rename = "server_error")]
+                ServerError { message: String },
+            }
+        };
+        let schema: serde_json::Value = serde_json::from_str(&api_error_schema(&input)).unwrap();
+        assert_eq!(schema["required"], serde_json::json!(["error"]));
+        let error = &schema["properties"]["error"];
+        assert_eq!(error["oneOf"].as_array().unwrap().len(), 2);
+        assert_eq!(error["discriminator"]["propertyName"], "error");
+    }
+    
+    #[test]
+    fn test_variant_pattern() {
+        let input: syn::ItemEnum = parse_quote! {
+            enum ApiError {
+                NotFound { id: u32 },
+                Timeout(std::time::Duration, String),
+                Unavailable,
+            }
+        };
+        let patterns: Vec<String> = input.variants.iter().map(|variant| variant_pattern(variant).to_string()).collect();
+        assert_eq!(patterns, ["Self :: NotFound { .. }", "Self :: Timeout (..)", "Self :: Unavailable"]);
+    }
+    
+    #[test]
+    fn test_variant_status() {
+        let variant: syn::Variant = parse_quote! {
+            /// 404: User not found
+            NotFound { id: u32 }
+        };
+        assert_eq!(variant_status(&variant), (404, "User not found".to_string()));
+        
+        let variant: syn::Variant = parse_quote! {
+            /// CONFLICT: Email already taken
+            Conflict
+        };
+        assert_eq!(variant_status(&variant), (409, "Email already taken".to_string()));
+        
+        let variant: syn::Variant = parse_quote! { Unexpected };
+        assert_eq!(variant_status(&variant), (500, "Unexpected".to_string()));
+    }
     
-    let expanded = quote! {
-        impl stonehm::StonehmSchema for #name {
-            fn schema() -> String {
-                #schema_json.to_string()
+    #[test]
+    fn test_serde_flatten_fields() {
+        let input: DeriveInput = parse_quote! {
+            struct User {
+                #[serde(flatten)]
+                base: CommonFields,
+                #[serde(default)]
+                name: String,
             }
-        }
+        };
         
-        // Register this type's schema for OpenAPI components
-        stonehm::inventory::submit! {
-            stonehm::SchemaRegistration {
-                type_name: #name_str,
-                schema_json: #schema_json,
+        let schema = generate_schema_json(&input);
+        assert_eq!(
+            schema,
+            r##"{"allOf":[{"$ref":"#/components/schemas/CommonFields"},{"type":"object","properties":{"name":{"type":"string"}}}]}"##
+        );
+        
+        let input: DeriveInput = parse_quote! {
+            struct Extensible {
+                id: u32,
+                #[serde(flatten)]
+                extra: HashMap<String, Value>,
             }
-        }
-    };
+        };
+        let schema = generate_schema_json(&input);
+        assert!(schema.starts_with(r#"{"type":"object""#));
+        assert!(!schema.contains("extra"));
+        assert!(schema.ends_with(r#""additionalProperties":true}"#));
+    }
     
-    TokenStream::from(expanded)
-}
-
-/// Attribute macro for automatically generating HTTP error responses.
-/// 
-/// This macro automatically implements `axum::response::IntoResponse` for error enums,
-/// mapping each variant to an appropriate HTTP status code. Use doc comments with
-/// `/// {code}: {description}` format to specify status codes for variants.
-/// 
-/// # Basic Usage
-/// 
-/// ```rust
-/// use stonehm_macros::api_error;
-/// 
-/// #[api_error]
-/// enum ApiError {
-///     /// 404: User not found
-///     UserNotFound { id: u32 },
-///     
-///     /// 400: Invalid input provided
-///     InvalidInput { message: String },
-///     
-///     /// 401: Authentication required
-///     Unauthorized,
-///     
-///     /// 403: Access forbidden
-///     Forbidden,
-///     
-///     // Variants without doc comments default to 500 Internal Server Error
-///     DatabaseError,
-///     NetworkTimeout,
-/// }
-/// ```
-/// 
-/// # Generated Implementation
-/// 
-/// The macro automatically generates:
-/// - `IntoResponse` implementation for HTTP responses
-/// - `Serialize` implementation for JSON serialization  
-/// - `StonehmSchema` implementation for OpenAPI documentation
-/// - Maps each variant to its specified status code
-/// - Uses 500 Internal Server Error for variants without doc comments
-/// - Serializes the error as JSON in the response body
-/// 
-/// # Supported Status Codes
-/// 
-/// Common HTTP status codes you can use:
-/// - 200 OK
-/// - 201 Created  
-/// - 204 No Content
-/// - 400 Bad Request
-/// - 401 Unauthorized
-/// - 403 Forbidden
-/// - 404 Not Found
-/// - 409 Conflict
-/// - 422 Unprocessable Entity
-/// - 500 Internal Server Error
-/// - 502 Bad Gateway
-/// - 503 Service Unavailable
-/// 
-/// # Examples
-/// 
-/// ## Basic Error Enum
-/// 
-/// ```rust
-/// # use stonehm_macros::api_error;
-/// # use serde::Serialize;
-/// #[api_error]
-/// #[derive(Serialize)]
-/// enum UserError {
-///     /// 404: User not found
-///     NotFound { id: u32 },
-///     
-///     /// 400: Invalid user data
-///     InvalidData { field: String, reason: String },
-/// }
-/// ```
-/// 
-/// ## With Custom Serialization
-/// 
-/// ```rust
-/// # use stonehm_macros::api_error;
-/// # use serde::Serialize;
-/// #[api_error]
-/// #[derive(Serialize)]
-/// #[serde(tag = "error", content = "details")]
-/// enum ApiError {
-///     /// 401: Missing or invalid authentication token
-///     #[serde(rename = "auth_required")]
-///     AuthRequired,
-///     
-///     /// 403: User lacks required permissions
-///     #[serde(rename = "access_denied")]
-///     AccessDenied { required_role: String },
-/// }
-/// ```
-/// 
-/// ## Usage in Handlers
-/// 
-/// ```rust,no_run
-/// # use axum::Json;
-/// # use stonehm_macros::{api_error, api_handler, StoneSchema};
-/// # use serde::{Serialize, Deserialize};
-/// # #[derive(Deserialize, StoneSchema)]
-/// # struct UpdateUserRequest { name: String }
-/// # #[derive(Serialize, StoneSchema)]
-/// # struct User { id: u32, name: String }
-/// # #[api_error]
-/// # #[derive(Serialize)]
-/// # enum UserError {
-/// #     /// 404: User not found
-/// #     NotFound { id: u32 },
-/// #     /// 400: Invalid data
-/// #     InvalidData { message: String },
-/// # }
-/// 
-/// /// Update user information
-/// #[api_handler]
-/// async fn update_user(
-///     axum::extract::Path(id): axum::extract::Path<u32>,
-///     Json(data): Json<UpdateUserRequest>
-/// ) -> Result<Json<User>, UserError> {
-///     if id == 0 {
-///         return Err(UserError::NotFound { id });
-///     }
-///     
-///     if data.name.is_empty() {
-///         return Err(UserError::InvalidData { 
-///             message: "Name cannot be empty".to_string() 
-///         });
-///     }
-///     
-///     Ok(Json(User { id, name: data.name }))
-/// }
-/// ```
-/// 
-/// # Requirements
-/// 
-/// - The error enum must also have `#[derive(Serialize)]` or implement `Serialize` manually
-/// - Each variant's doc comment should start with a 3-digit HTTP status code followed by a colon
-/// - The macro will automatically implement `axum::response::IntoResponse`
-/// - The macro will register the error schema for OpenAPI documentation
-#[proc_macro_attribute]
-pub fn api_error(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(item as DeriveInput);
-    let name = &input.ident;
-    let name_str = name.to_string();
+    #[test]
+    fn test_stone_pattern_properties() {
+        let input: DeriveInput = parse_quote! {
+            struct Translations {
+                #[stone(pattern_properties(pattern = "^[a-z]{2}$", type = "string"), deprecated)]
+                labels: HashMap<String, String>,
+                #[stone(pattern_properties(pattern = "^v[0-9]+$"))]
+                versions: HashMap<String, String>,
+            }
+        };
+        
+        let schema: serde_json::Value = serde_json::from_str(&generate_schema_json(&input)).unwrap();
+        let labels = &schema["properties"]["labels"];
+        assert_eq!(labels["type"], "object");
+        assert_eq!(labels["additionalProperties"]["type"], "string");
+        assert_eq!(labels["x-patternProperties"]["^[a-z]{2}$"]["type"], "string");
+        assert_eq!(labels["deprecated"], true);
+        assert_eq!(schema["properties"]["versions"]["x-patternProperties"]["^v[0-9]+$"]["type"], "string");
+    }
     
-    // Extract status codes from doc comments
-    let mut variant_status_codes = Vec::new();
+    #[test]
+    fn test_custom_types_are_refs_and_primitives_are_not() {
+        let input: DeriveInput = parse_quote! {
+            struct Account {
+                preferences: UserPreferences,
+                small: u8,
+                signed: i16,
+                huge: u128,
+                initial: char,
+                metadata: HashMap<String, String>,
+            }
+        };
+        
+        let schema = generate_schema_json(&input);
+        assert!(schema.contains(r##""preferences":{"$ref":"#/components/schemas/UserPreferences"}"##));
+        assert!(schema.contains(r#""small":{"type":"integer","format":"int32","minimum":0}"#));
+        assert!(schema.contains(r#""signed":{"type":"integer","format":"int32"}"#));
+        assert!(schema.contains(r#""huge":{"type":"integer","minimum":0}"#));
+        assert!(schema.contains(r#""initial":{"type":"string"}"#));
+        assert!(schema.contains(r#""metadata":{"type":"object","additionalProperties":{"type":"string"}}"#));
+    }
     
-    if let Data::Enum(data_enum) = &input.data {
-        for variant in &data_enum.variants {
-            let variant_name = &variant.ident;
-            let mut status_code = 500u16; // Default to 500 Internal Server Error
-            
-            // Look for status code in doc comments
-            for attr in &variant.attrs {
-                if attr.path().is_ident("doc") {
-                    if let Meta::NameValue(meta) = &attr.meta {
-                        if let Expr::Lit(lit) = &meta.value {
-                            if let Lit::Str(s) = &lit.lit {
-                                let doc = s.value();
-                                // Look for pattern like "404: Description" or "/// 404 Description"
-                                if let Some(colon_pos) = doc.find(':') {
-                                    let code_part = doc[..colon_pos].trim();
-                                    if let Ok(code) = code_part.parse::<u16>() {
-                                        status_code = code;
-                                        break;
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+    #[test]
+    fn test_well_known_type_formats() {
+        let input: DeriveInput = parse_quote! {
+            struct Session {
+                id: uuid::Uuid,
+                created_at: chrono::DateTime<chrono::Utc>,
+                expires_at: Option<NaiveDateTime>,
+                /// Where to send the user afterwards
+                callback: url::Url,
+                client_ip: std::net::IpAddr,
+                gateway: Ipv4Addr,
+                related: Vec<Uuid>,
             }
-            
-            variant_status_codes.push((variant_name.clone(), status_code));
-        }
+        };
+        
+        let schema = generate_schema_json(&input);
+        assert!(schema.contains(r#""id":{"type":"string","format":"uuid"}"#));
+        assert!(schema.contains(r#""created_at":{"type":"string","format":"date-time"}"#));
+        assert!(schema.contains(r#""expires_at":{"type":"string","format":"date-time","nullable":true}"#));
+        assert!(schema.contains(r#""callback":{"type":"string","format":"uri","description":"Where to send the user afterwards"}"#));
+        assert!(schema.contains(r#""client_ip":{"type":"string","anyOf":[{"format":"ipv4"},{"format":"ipv6"}]}"#));
+        assert!(schema.contains(r#""gateway":{"type":"string","format":"ipv4"}"#));
+        assert!(schema.contains(r#""related":{"type":"array","items":{"type":"string","format":"uuid"}}"#));
+        assert!(!schema.contains("$ref"));
     }
     
-    // Generate match arms for IntoResponse implementation
-    let match_arms = variant_status_codes.iter().map(|(variant_name, status_code)| {
-        quote! {
-            Self::#variant_name { .. } => #status_code
-        }
-    });
+    #[test]
+    fn test_string_keyed_maps() {
+        let input: DeriveInput = parse_quote! {
+            struct Config {
+                labels: BTreeMap<String, String>,
+                limits: HashMap<String, u32>,
+                owners: HashMap<String, User>,
+                groups: Option<HashMap<String, Vec<String>>>,
+                by_id: HashMap<u64, String>,
+            }
+        };
+        
+        let schema = generate_schema_json(&input);
+        assert!(schema.contains(r#""labels":{"type":"object","additionalProperties":{"type":"string"}}"#));
+        assert!(schema.contains(r#""limits":{"type":"object","additionalProperties":{"type":"integer","format":"int32","minimum":0}}"#));
+        assert!(schema.contains(r##""owners":{"type":"object","additionalProperties":{"$ref":"#/components/schemas/User"}}"##));
+        assert!(schema.contains(r#""groups":{"type":"object","additionalProperties":{"type":"array","items":{"type":"string"}},"nullable":true}"#));
+        // Non-string keys fall back to a plain object
+        assert!(schema.contains(r#""by_id":{"type":"object"}"#));
+    }
     
-    // Generate the implementation
-    let expanded = quote! {
-        #input
+    #[test]
+    fn test_serde_rename_on_fields() {
+        let input: DeriveInput = parse_quote! {
+            struct Login {
+                #[serde(rename = "userName")]
+                user_name: String,
+                #[serde(default, rename = "rememberMe")]
+                remember_me: Option<bool>,
+                password: String,
+            }
+        };
         
-        impl axum::response::IntoResponse for #name {
-            fn into_response(self) -> axum::response::Response {
-                use axum::http::StatusCode;
-                
-                let status = match &self {
-                    #(#match_arms),*
-                };
-                
-                let body = axum::Json(serde_json::json!({
-                    "error": serde_json::to_value(&self).unwrap_or_else(|_| serde_json::json!({
-                        "message": "Failed to serialize error"
-                    }))
-                }));
-                
-                (StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR), body).into_response()
+        let schema = generate_schema_json(&input);
+        assert!(schema.contains(r#""userName":{"type":"string"}"#));
+        assert!(schema.contains(r#""rememberMe":{"type":"boolean","nullable":true}"#));
+        assert!(!schema.contains("user_name"));
+        assert!(schema.contains(r#""required":["userName","password"]"#));
+    }
+    
+    #[test]
+    fn test_serde_rename_all_on_struct() {
+        let input: DeriveInput = parse_quote! {
+            #[serde(rename_all = "camelCase")]
+            struct UserResponse {
+                user_id: u64,
+                display_name: Option<String>,
+                #[serde(rename = "e-mail")]
+                email_address: String,
             }
-        }
+        };
         
-        // Also implement StonehmSchema for the error type
-        impl stonehm::StonehmSchema for #name {
-            fn schema() -> String {
-                // For error enums, generate a simple schema
-                // In a real implementation, this would analyze variants
-                format!(r#"{{"type":"object","properties":{{"error":{{"type":"object"}}}}}}"#)
+        let schema = generate_schema_json(&input);
+        assert!(schema.contains(r#""userId":{"type":"integer","format":"int64","minimum":0}"#));
+        assert!(schema.contains(r#""displayName":{"type":"string","nullable":true}"#));
+        assert!(schema.contains(r#""e-mail":{"type":"string"}"#));
+        assert!(schema.contains(r#""required":["userId","e-mail"]"#));
+        
+        assert_eq!(rename_field("user_id", "PascalCase"), "UserId");
+        assert_eq!(rename_field("user_id", "kebab-case"), "user-id");
+        assert_eq!(rename_field("user_id", "SCREAMING_SNAKE_CASE"), "USER_ID");
+        assert_eq!(rename_field("user_id", "SCREAMING-KEBAB-CASE"), "USER-ID");
+        assert_eq!(rename_field("user_id", "snake_case"), "user_id");
+    }
+    
+    #[test]
+    fn test_field_doc_comments_become_descriptions() {
+        let input: DeriveInput = parse_quote! {
+            struct User {
+                /// The user's primary email
+                email: String,
+                /// Age in "years"
+                /// if known
+                age: Option<u32>,
+                /// Free-form labels
+                #[stone(deprecated)]
+                labels: Vec<String>,
+                /// Anything else
+                extra: serde_json::Value,
+                nickname: String,
             }
-        }
+        };
         
-        // Register this error type's schema
-        stonehm::inventory::submit! {
-            stonehm::SchemaRegistration {
-                type_name: #name_str,
-                schema_json: r#"{"type":"object","properties":{"error":{"type":"object"}}}"#,
+        let schema = generate_schema_json(&input);
+        assert!(schema.contains(r#""email":{"type":"string","description":"The user's primary email"}"#));
+        assert!(schema.contains(r#""age":{"type":"integer","format":"int32","minimum":0,"nullable":true,"description":"Age in \"years\" if known"}"#));
+        assert!(schema.contains(r#""labels":{"type":"array","items":{"type":"string"},"description":"Free-form labels","deprecated":true}"#));
+        assert!(schema.contains(r#""extra":{"description":"Anything else"}"#));
+        assert!(schema.contains(r#""nickname":{"type":"string"}"#));
+        assert!(serde_json::from_str::<serde_json::Value>(&schema).is_ok());
+    }
+    
+    #[test]
+    fn test_vec_fields_are_arrays() {
+        let input: DeriveInput = parse_quote! {
+            struct Team {
+                tags: Vec<String>,
+                members: Vec<User>,
+                grid: Vec<Vec<f64>>,
+                flags: Vec<bool>,
             }
-        }
-    };
+        };
+        
+        let schema = generate_schema_json(&input);
+        assert!(schema.contains(r#""tags":{"type":"array","items":{"type":"string"}}"#));
+        assert!(schema.contains(r##""members":{"type":"array","items":{"$ref":"#/components/schemas/User"}}"##));
+        assert!(schema.contains(r#""grid":{"type":"array","items":{"type":"array","items":{"type":"number","format":"double"}}}"#));
+        assert!(schema.contains(r#""flags":{"type":"array","items":{"type":"boolean"}}"#));
+        assert!(schema.contains(r#""required":["tags","members","grid","flags"]"#));
+    }
     
-    TokenStream::from(expanded)
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use quote::quote;
-    use syn::parse_quote;
+    #[test]
+    fn test_option_fields_use_inner_type() {
+        let input: DeriveInput = parse_quote! {
+            struct Profile {
+                name: String,
+                age: Option<u32>,
+                verified: Option<bool>,
+                manager: Option<User>,
+                #[stone(exclusive_minimum = 0)]
+                rating: Option<f64>,
+            }
+        };
+        
+        let schema = generate_schema_json(&input);
+        assert!(schema.contains(r#""age":{"type":"integer","format":"int32","minimum":0,"nullable":true}"#));
+        assert!(schema.contains(r#""verified":{"type":"boolean","nullable":true}"#));
+        assert!(schema.contains(r##""manager":{"allOf":[{"$ref":"#/components/schemas/User"}],"nullable":true}"##));
+        assert!(schema.contains(r#""rating":{"type":"number","format":"double","nullable":true,"minimum":0,"exclusiveMinimum":true}"#));
+        assert!(schema.contains(r#""required":["name"]"#));
+    }
     
     #[test]
-    fn test_extract_request_body_type() {
-        // Test Json<T> extraction
-        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
-            Json(body): Json<CreateUserRequest>
+    fn test_nullable_array_vs_array_of_nullable_items() {
+        let input: DeriveInput = parse_quote! {
+            struct Post {
+                labels: Option<Vec<String>>,
+                scores: Vec<Option<u32>>,
+                author: Option<Author>,
+                #[stone(deprecated)]
+                legacy: Option<String>,
+            }
         };
         
-        let result = extract_request_body_type(&inputs);
-        assert_eq!(result, Some("CreateUserRequest".to_string()));
+        let schema = generate_schema_json(&input);
+        assert!(schema.contains(r#""labels":{"type":"array","items":{"type":"string"},"nullable":true}"#));
+        assert!(schema.contains(r#""scores":{"type":"array","items":{"type":"integer","format":"int32","minimum":0,"nullable":true}}"#));
+        assert!(schema.contains(r##""author":{"allOf":[{"$ref":"#/components/schemas/Author"}],"nullable":true}"##));
+        assert!(schema.contains(r#""legacy":{"type":"string","nullable":true,"deprecated":true}"#));
+        // Only the non-optional collection is required
+        assert!(schema.contains(r#""required":["scores"]"#));
+    }
+    
+    #[test]
+    fn test_optional_json_value_with_markers() {
+        let input: DeriveInput = parse_quote! {
+            struct Event {
+                /// Free-form payload
+                #[stone(deprecated)]
+                payload: Option<serde_json::Value>,
+                extras: Vec<Option<serde_json::Value>>,
+            }
+        };
         
-        // Test with multiple parameters
-        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
-            Path(id): Path<u32>,
-            Json(data): Json<UpdateRequest>
+        let schema: serde_json::Value = serde_json::from_str(&generate_schema_json(&input)).unwrap();
+        assert_eq!(
+            schema["properties"]["payload"],
+            serde_json::json!({"nullable": true, "description": "Free-form payload", "deprecated": true})
+        );
+        assert_eq!(schema["properties"]["extras"]["items"], serde_json::json!({"nullable": true}));
+    }
+    
+    #[test]
+    fn test_serde_skipped_fields_are_omitted() {
+        let input: DeriveInput = parse_quote! {
+            struct Account {
+                name: String,
+                #[serde(skip)]
+                cache: Vec<u8>,
+                #[serde(skip_serializing)]
+                password: String,
+                #[serde(skip_deserializing, default)]
+                created_by: String,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                nickname: Option<String>,
+            }
         };
         
-        let result = extract_request_body_type(&inputs);
-        assert_eq!(result, Some("UpdateRequest".to_string()));
+        let schema = generate_schema_json(&input);
+        assert!(!schema.contains("cache"));
+        assert!(!schema.contains("password"));
+        assert!(!schema.contains("created_by"));
+        assert!(schema.contains(r#""nickname":{"type":"string","nullable":true}"#));
+        assert!(schema.contains(r#""required":["name"]"#));
+    }
+    
+    #[test]
+    fn test_serde_default_fields_are_not_required() {
+        let input: DeriveInput = parse_quote! {
+            struct Settings {
+                name: String,
+                #[serde(default)]
+                retries: u8,
+                #[serde(default = "default_theme")]
+                theme: String,
+            }
+        };
+        let schema = generate_schema_json(&input);
+        assert!(schema.contains(r#""theme":{"type":"string"}"#));
+        assert!(schema.contains(r#""required":["name"]"#));
         
-        // Test without Json parameter
-        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
-            Path(id): Path<u32>
+        let input: DeriveInput = parse_quote! {
+            #[serde(default)]
+            struct Paging {
+                page: u32,
+                per_page: u32,
+            }
+        };
+        let schema = generate_schema_json(&input);
+        assert!(!schema.contains("required"));
+    }
+    
+    #[test]
+    fn test_stone_validation_keywords() {
+        let input: DeriveInput = parse_quote! {
+            struct Signup {
+                #[stone(minimum = 0, maximum = 120)]
+                age: u8,
+                #[stone(min_length = 1, max_length = 255, pattern = "^[a-z]+$")]
+                username: String,
+                #[stone(enum = ["admin", "member"])]
+                role: Option<String>,
+                #[stone(minimum = -1.5)]
+                offset: f64,
+            }
         };
         
-        let result = extract_request_body_type(&inputs);
-        assert_eq!(result, None);
+        let schema = generate_schema_json(&input);
+        // An explicit minimum replaces the implicit unsigned one
+        assert!(schema.contains(r#""age":{"type":"integer","format":"int32","minimum":0,"maximum":120}"#));
+        assert!(schema.contains(r#""username":{"type":"string","minLength":1,"maxLength":255,"pattern":"^[a-z]+$"}"#));
+        assert!(schema.contains(r#""role":{"type":"string","nullable":true,"enum":["admin","member"]}"#));
+        assert!(schema.contains(r#""offset":{"type":"number","format":"double","minimum":-1.5}"#));
     }
     
     #[test]
-    fn test_extract_response_and_error_types() {
-        // Test Result<Json<T>, E>
-        let output: ReturnType = parse_quote! {
-            -> Result<Json<UserResponse>, ApiError>
+    fn test_sized_number_formats() {
+        let input: DeriveInput = parse_quote! {
+            struct Stats {
+                count: i64,
+                total: usize,
+                ratio: f32,
+                #[stone(exclusive_minimum = 1)]
+                retries: u16,
+                #[stone(exclusive_minimum = 1)]
+                limit: Option<u32>,
+            }
         };
         
-        let (response_type, error_type) = extract_response_and_error_types(&output);
-        assert_eq!(response_type, Some("UserResponse".to_string()));
-        assert_eq!(error_type, Some("ApiError".to_string()));
+        let schema = generate_schema_json(&input);
+        assert!(schema.contains(r#""count":{"type":"integer","format":"int64"}"#));
+        assert!(schema.contains(r#""total":{"type":"integer","format":"int64","minimum":0}"#));
+        assert!(schema.contains(r#""ratio":{"type":"number","format":"float"}"#));
+        // An explicit exclusive minimum replaces the implicit unsigned minimum
+        assert!(schema.contains(r#""retries":{"type":"integer","format":"int32","minimum":1,"exclusiveMinimum":true}"#));
+        assert!(schema.contains(r#""limit":{"type":"integer","format":"int32","nullable":true,"minimum":1,"exclusiveMinimum":true}"#));
+        assert!(serde_json::from_str::<serde_json::Value>(&schema).is_ok());
+    }
+    
+    #[test]
+    fn test_unit_enum_values() {
+        let input: DeriveInput = parse_quote! {
+            #[serde(rename_all = "snake_case")]
+            enum Status {
+                Active,
+                OnHold,
+                #[serde(rename = "gone")]
+                Archived,
+            }
+        };
+        assert_eq!(generate_schema_json(&input), r#"{"type":"string","enum":["active","on_hold","gone"]}"#);
         
-        // Test Json<T> without Result
-        let output: ReturnType = parse_quote! {
-            -> Json<HealthResponse>
+    }
+    
+    #[test]
+    fn test_data_enum_one_of() {
+        let schema = |input: DeriveInput| serde_json::from_str::<serde_json::Value>(&generate_schema_json(&input)).unwrap();
+        
+        // Externally tagged (serde's default): each variant is keyed by its name
+        let value = schema(parse_quote! {
+            enum Shape {
+                Circle { radius: f64 },
+                Square { side: f64 },
+                Empty,
+            }
+        });
+        assert_eq!(value["oneOf"][0], serde_json::json!({
+            "type": "object",
+            "properties": {"Circle": {"type": "object", "properties": {"radius": {"type": "number", "format": "double"}}, "required": ["radius"]}},
+            "required": ["Circle"],
+        }));
+        assert_eq!(value["oneOf"][1]["properties"]["Square"]["required"], serde_json::json!(["side"]));
+        assert_eq!(value["oneOf"][2], serde_json::json!({"type": "string", "enum": ["Empty"]}));
+        assert!(value.get("discriminator").is_none());
+        
+        // Internally tagged: the tag sits beside the variant's fields and is the discriminator
+        let value = schema(parse_quote! {
+            #[serde(tag = "kind", rename_all = "lowercase")]
+            enum Shape {
+                Circle { radius: f64 },
+                Labeled(Label),
+                Empty,
+            }
+        });
+        assert_eq!(value["discriminator"], serde_json::json!({"propertyName": "kind"}));
+        assert_eq!(value["oneOf"][0]["properties"]["kind"], serde_json::json!({"type": "string", "enum": ["circle"]}));
+        assert_eq!(value["oneOf"][0]["required"], serde_json::json!(["kind", "radius"]));
+        assert_eq!(value["oneOf"][1]["allOf"][1]["$ref"], "#/components/schemas/Label");
+        assert_eq!(value["oneOf"][2]["required"], serde_json::json!(["kind"]));
+        
+        // Adjacently tagged: tag and content side by side
+        let value = schema(parse_quote! {
+            #[serde(tag = "t", content = "c")]
+            enum Message {
+                Text(String),
+                Point(i32, i32),
+            }
+        });
+        assert_eq!(value["oneOf"][0]["properties"]["c"], serde_json::json!({"type": "string"}));
+        assert_eq!(value["oneOf"][1]["properties"]["c"]["maxItems"], 2);
+        assert_eq!(value["oneOf"][1]["required"], serde_json::json!(["t", "c"]));
+        
+        // Untagged: just the variant bodies
+        let value = schema(parse_quote! {
+            #[serde(untagged)]
+            enum Id {
+                Number(u64),
+                Text(String),
+            }
+        });
+        assert_eq!(value, serde_json::json!({"oneOf": [{"type": "integer", "format": "int64", "minimum": 0}, {"type": "string"}]}));
+    }
+    
+    #[test]
+    fn test_string_like_fields() {
+        let input: DeriveInput = parse_quote! {
+            struct Borrowed<'a> {
+                owned: String,
+                borrowed: &'a str,
+                cow: Cow<'a, str>,
+                boxed: Box<str>,
+                shared: std::sync::Arc<str>,
+            }
         };
         
-        let (response_type, error_type) = extract_response_and_error_types(&output);
-        assert_eq!(response_type, Some("HealthResponse".to_string()));
-        assert_eq!(error_type, None);
+        let schema = generate_schema_json(&input);
+        for field in ["owned", "borrowed", "cow", "boxed", "shared"] {
+            assert!(schema.contains(&format!(r#""{field}":{{"type":"string"}}"#)), "{field}: {schema}");
+        }
+        assert!(schema.contains(r#""required":["owned","borrowed","cow","boxed","shared"]"#));
         
-        // Test Result with tuple success type
-        let output: ReturnType = parse_quote! {
-            -> Result<(StatusCode, Json<CreatedResponse>), CreateError>
+        assert!(!is_string_like(&parse_quote!(Cow<'a, [u8]>)));
+        assert!(!is_string_like(&parse_quote!(&'a u32)));
+    }
+    
+    #[test]
+    fn test_stone_server_generated_field() {
+        let input: DeriveInput = parse_quote! {
+            struct User {
+                #[stone(server_generated)]
+                id: u64,
+                #[stone(server_generated, deprecated)]
+                created_at: String,
+                name: String,
+            }
         };
         
-        let (response_type, error_type) = extract_response_and_error_types(&output);
-        assert_eq!(response_type, None); // Current implementation doesn't handle tuples
-        assert_eq!(error_type, Some("CreateError".to_string()));
+        let schema = generate_schema_json(&input);
+        assert!(schema.contains(r#""id":{"type":"integer","format":"int64","minimum":0,"readOnly":true}"#));
+        assert!(schema.contains(r#""created_at":{"type":"string","deprecated":true,"readOnly":true}"#));
+        assert!(schema.contains(r#""name":{"type":"string"}"#));
+    }
+    
+    #[test]
+    fn test_stone_deprecated_container() {
+        let input: DeriveInput = parse_quote! {
+            #[stone(deprecated)]
+            struct LegacyUser {
+                name: String,
+            }
+        };
         
-        // Test no return type
-        let output: ReturnType = ReturnType::Default;
+        let schema = generate_schema_json(&input);
+        assert!(schema.starts_with(r#"{"deprecated":true,"type":"object""#));
+        assert!(!schema.contains(r#""name":{"type":"string","deprecated":true}"#));
+    }
+    
+    #[test]
+    fn test_nested_struct_field_refs() {
+        let input: DeriveInput = parse_quote! {
+            struct Customer {
+                /// Where invoices are sent
+                billing: Address,
+                shipping: Address,
+                #[stone(deprecated)]
+                legacy: Address,
+                tags: Vec<String>,
+            }
+        };
         
-        let (response_type, error_type) = extract_response_and_error_types(&output);
-        assert_eq!(response_type, None);
-        assert_eq!(error_type, None);
+        let schema = generate_schema_json(&input);
+        assert!(schema.contains(r##""billing":{"allOf":[{"$ref":"#/components/schemas/Address"}],"description":"Where invoices are sent"}"##));
+        assert!(schema.contains(r##""shipping":{"$ref":"#/components/schemas/Address"}"##));
+        assert!(schema.contains(r##""legacy":{"allOf":[{"$ref":"#/components/schemas/Address"}],"deprecated":true}"##));
+        assert!(schema.contains(r#""tags":{"type":"array","items":{"type":"string"}}"#));
     }
     
     #[test]
-    fn test_sanitize_type_for_identifier() {
-        assert_eq!(sanitize_type_for_identifier("Vec<String>"), "Vec_String_");
-        assert_eq!(sanitize_type_for_identifier("HashMap<String, Value>"), "HashMap_String_Value_");
-        assert_eq!(sanitize_type_for_identifier("Option<User>"), "Option_User_");
-        assert_eq!(sanitize_type_for_identifier("Result<T, E>"), "Result_T_E_");
-        assert_eq!(sanitize_type_for_identifier("&str"), "_str");
-        assert_eq!(sanitize_type_for_identifier("*const u8"), "_const_u8");
+    fn test_json_value_field_is_unconstrained() {
+        let input: DeriveInput = parse_quote! {
+            struct Event {
+                metadata: serde_json::Value,
+                #[stone(deprecated)]
+                extra: Value,
+            }
+        };
+        
+        let schema = generate_schema_json(&input);
+        assert!(schema.contains(r#""metadata":{}"#));
+        assert!(schema.contains(r#""extra":{"deprecated":true}"#));
+        assert!(schema.contains(r#""required":["metadata","extra"]"#));
     }
     
     #[test]
-    fn test_extract_docs_simple() {
-        let attrs = vec![
-            parse_quote!(#[doc = " Simple handler"]),
-            parse_quote!(#[doc = " "]),
-            parse_quote!(#[doc = " This is a simple test handler"]),
-        ];
+    fn test_stone_exclusive_bounds() {
+        let input: DeriveInput = parse_quote! {
+            struct Reading {
+                #[stone(exclusive_minimum = 0, exclusive_maximum = 100)]
+                percent: f64,
+                #[stone(exclusive_minimum = -5, deprecated)]
+                offset: i32,
+                #[stone(exclusive_minimum = 1)]
+                label: String,
+            }
+        };
         
-        let docs = extract_docs(&attrs);
-        assert_eq!(docs.summary, Some("Simple handler".to_string()));
-        assert_eq!(docs.description, Some("This is a simple test handler".to_string()));
+        let schema = generate_schema_json(&input);
+        assert!(schema.contains(r#""percent":{"type":"number","format":"double","minimum":0,"exclusiveMinimum":true,"maximum":100,"exclusiveMaximum":true}"#));
+        assert!(schema.contains(r#""offset":{"type":"integer","format":"int32","minimum":-5,"exclusiveMinimum":true,"deprecated":true}"#));
+        assert!(schema.contains(r#""label":{"type":"string"}"#));
     }
     
     #[test]
-    fn test_extract_docs_with_parameters() {
-        let attrs = vec![
-            parse_quote!(#[doc = " Get user by ID"]),
-            parse_quote!(#[doc = " "]),
-            parse_quote!(#[doc = " Retrieves user information"]),
-            parse_quote!(#[doc = " "]),
-            parse_quote!(#[doc = " # Parameters"]),
-            parse_quote!(#[doc = " - id (path): User ID"]),
-            parse_quote!(#[doc = " - include_deleted (query): Include deleted users"]),
-        ];
+    fn test_parse_handler_args() {
+        let args = parse_handler_args(quote!()).unwrap();
+        assert!(args.tags.is_empty());
+        assert!(!args.echo);
         
-        let docs = extract_docs(&attrs);
-        assert_eq!(docs.summary, Some("Get user by ID".to_string()));
-        assert_eq!(docs.parameters.len(), 2);
-        assert_eq!(docs.parameters[0].name, "id");
-        assert_eq!(docs.parameters[0].param_type, "path");
-        assert_eq!(docs.parameters[1].name, "include_deleted");
-        assert_eq!(docs.parameters[1].param_type, "query");
+        let args = parse_handler_args(quote!("user", "admin")).unwrap();
+        assert_eq!(args.tags, vec!["user", "admin"]);
+        assert!(!args.echo);
+        
+        let args = parse_handler_args(quote!("echo", echo)).unwrap();
+        assert_eq!(args.tags, vec!["echo"]);
+        assert!(args.echo);
+        
+        let args = parse_handler_args(quote!("user", operation_id = "listUsers")).unwrap();
+        assert_eq!(args.tags, vec!["user"]);
+        assert_eq!(args.operation_id.as_deref(), Some("listUsers"));
+        assert!(!args.internal);
+        
+        let args = parse_handler_args(quote!("admin", internal)).unwrap();
+        assert!(args.internal);
+        
+        let args = parse_handler_args(quote!(response_content_type = "application/vnd.myapi.v1+json")).unwrap();
+        assert_eq!(args.response_content_type.as_deref(), Some("application/vnd.myapi.v1+json"));
+        
+        let args = parse_handler_args(quote!("users", success_status = 201)).unwrap();
+        assert_eq!(args.success_status, Some(201));
+        assert!(parse_handler_args(quote!(success_status = 404)).is_err());
+        assert!(parse_handler_args(quote!(success_status = "201")).is_err());
+        
+        assert!(parse_handler_args(quote!(unknown_flag)).is_err());
+        assert!(parse_handler_args(quote!(operation_id = 3)).is_err());
+        
+        let args = parse_handler_args(quote!(security = "oauth2", scopes("read:users", "write:users"))).unwrap();
+        assert_eq!(args.security.as_deref(), Some("oauth2"));
+        assert_eq!(args.scopes, vec!["read:users", "write:users"]);
+        assert_eq!(security_requirements_json(args.security.as_deref(), &args.scopes, &[]), r#"[{"oauth2":["read:users","write:users"]}]"#);
+        
+        let lines = vec!["bearerAuth".to_string(), "oauth2: read:users, admin".to_string()];
+        assert_eq!(
+            security_requirements_json(None, &[], &lines),
+            r#"[{"bearerAuth":[]},{"oauth2":["read:users","admin"]}]"#
+        );
+        assert!(parse_handler_args(quote!(scopes("read:users"))).is_err());
+        assert!(parse_handler_args(quote!(unknown = "value")).is_err());
     }
     
     #[test]
-    fn test_extract_docs_with_request_body() {
+    fn test_extract_docs_multiple_multiline_examples() {
         let attrs = vec![
             parse_quote!(#[doc = " Create user"]),
             parse_quote!(#[doc = " "]),
-            parse_quote!(#[doc = " # Request Body"]),
-            parse_quote!(#[doc = " Content-Type: application/json"]),
-            parse_quote!(#[doc = " User data for creation"]),
-            parse_quote!(#[doc = " - name (string): Full name"]),
-            parse_quote!(#[doc = " - email (string): Email address"]),
+            parse_quote!(#[doc = " # Responses"]),
+            parse_quote!(#[doc = " - 400:"]),
+            parse_quote!(#[doc = "   description: Validation failed"]),
+            parse_quote!(#[doc = "   examples:"]),
+            parse_quote!(#[doc = "     - name: missing_field"]),
+            parse_quote!(#[doc = "       summary: Name is missing"]),
+            parse_quote!(#[doc = "       value: {"]),
+            parse_quote!(#[doc = r#"         "error": "name is required","#]),
+            parse_quote!(#[doc = r#"         "fields": ["name"]"#]),
+            parse_quote!(#[doc = "       }"]),
+            parse_quote!(#[doc = "     - name: invalid_email"]),
+            parse_quote!(#[doc = "       value:"]),
+            parse_quote!(#[doc = r#"         {"error": "email is invalid"}"#]),
+            parse_quote!(#[doc = "     - name: too_long"]),
+            parse_quote!(#[doc = r#"       value: {"error": "name is too long"}"#]),
+            parse_quote!(#[doc = " - 500: Server error"]),
         ];
         
         let docs = extract_docs(&attrs);
-        assert!(docs.request_body.is_some());
+        assert_eq!(docs.responses.len(), 2);
         
-        let body = docs.request_body.unwrap();
-        assert_eq!(body.content_type, "application/json");
-        assert_eq!(body.description, "User data for creation - name (string): Full name - email (string): Email address");
+        let examples = docs.responses[0].examples.as_ref().unwrap();
+        assert_eq!(examples.len(), 3);
+        assert_eq!(examples[0].name, "missing_field");
+        assert_eq!(examples[0].value, r#"{ "error": "name is required", "fields": ["name"] }"#);
+        assert_eq!(examples[1].name, "invalid_email");
+        assert_eq!(examples[1].value, r#"{"error": "email is invalid"}"#);
+        assert_eq!(examples[2].name, "too_long");
+        assert_eq!(examples[2].value, r#"{"error": "name is too long"}"#);
+        
+        assert_eq!(docs.responses[1].status_code, 500);
+        assert_eq!(docs.responses[1].description, "Server error");
+        
+        let json: serde_json::Value = serde_json::from_str(&response_examples_json(&docs.responses)).unwrap();
+        assert_eq!(json["400"]["missing_field"]["summary"], "Name is missing");
+        assert_eq!(json["400"]["missing_field"]["value"]["fields"][0], "name");
+        assert_eq!(json["400"]["too_long"]["value"]["error"], "name is too long");
+        assert!(json.get("500").is_none());
     }
     
     #[test]
-    fn test_extract_docs_with_responses() {
+    fn test_parse_status_code() {
+        assert_eq!(parse_status_code("200"), Some(200));
+        assert_eq!(parse_status_code("NOT_FOUND"), Some(404));
+        assert_eq!(parse_status_code("NO_CONTENT"), Some(204));
+        assert_eq!(parse_status_code("not_found"), None);
+        assert_eq!(parse_status_code("Content-Type"), None);
+        
         let attrs = vec![
-            parse_quote!(#[doc = " Delete user"]),
+            parse_quote!(#[doc = " Get user"]),
             parse_quote!(#[doc = " "]),
             parse_quote!(#[doc = " # Responses"]),
-            parse_quote!(#[doc = " - 204: User deleted"]),
-            parse_quote!(#[doc = " - 404: User not found"]),
-            parse_quote!(#[doc = " - 403: Access denied"]),
+            parse_quote!(#[doc = " - OK: User found"]),
+            parse_quote!(#[doc = " - NOT_FOUND: User missing"]),
         ];
-        
         let docs = extract_docs(&attrs);
-        assert_eq!(docs.responses.len(), 3);
+        let codes: Vec<u16> = docs.responses.iter().map(|r| r.status_code).collect();
+        assert_eq!(codes, vec![200, 404]);
+    }
+    
+    #[test]
+    fn test_split_request_examples() {
+        let lines: Vec<String> = [
+            "Content-Type: application/json",
+            "User to create",
+            "examples:",
+            "- name: basic",
+            "summary: Minimal user",
+            "value: {",
+            r#""name": "Ann""#,
+            "}",
+            "Content-Type: application/x-www-form-urlencoded",
+            "examples:",
+            "- name: basic",
+            "value: name=Ann",
+        ].iter().map(|l| l.to_string()).collect();
         
-        assert_eq!(docs.responses[0].status_code, 204);
-        assert_eq!(docs.responses[0].description, "User deleted");
+        let (remaining, examples) = split_request_examples(&lines);
+        assert_eq!(remaining, vec![
+            "Content-Type: application/json",
+            "User to create",
+            "Content-Type: application/x-www-form-urlencoded",
+        ]);
         
-        assert_eq!(docs.responses[1].status_code, 404);
-        assert_eq!(docs.responses[1].description, "User not found");
+        let examples: serde_json::Value = serde_json::from_str(&examples).unwrap();
+        assert_eq!(examples["application/json"]["basic"]["summary"], "Minimal user");
+        assert_eq!(examples["application/json"]["basic"]["value"]["name"], "Ann");
+        assert_eq!(examples["application/x-www-form-urlencoded"]["basic"]["value"], "name=Ann");
         
-        assert_eq!(docs.responses[2].status_code, 403);
-        assert_eq!(docs.responses[2].description, "Access denied");
+        let (_, examples) = split_request_examples(&["Content-Type: application/json".to_string()]);
+        assert_eq!(examples, "{}");
     }
     
     #[test]
-    fn test_extract_docs_complex_responses() {
-        let attrs = vec![
-            parse_quote!(#[doc = " Complex endpoint"]),
-            parse_quote!(#[doc = " "]),
-            parse_quote!(#[doc = " # Responses"]),
-            parse_quote!(#[doc = " - 200:"]),
-            parse_quote!(#[doc = "   description: Success"]),
-            parse_quote!(#[doc = "   content:"]),
-            parse_quote!(#[doc = "     application/json:"]),
-            parse_quote!(#[doc = "       schema: UserResponse"]),
-            parse_quote!(#[doc = " - 404:"]),
-            parse_quote!(#[doc = "   description: Not found"]),
-        ];
+    fn test_summary_and_description_without_placeholders() {
+        let lines = |lines: &[&str]| lines.iter().map(|line| line.to_string()).collect::<Vec<_>>();
         
-        let docs = extract_docs(&attrs);
-        assert_eq!(docs.responses.len(), 2);
+        assert_eq!(summary_and_description(&[]), (String::new(), Vec::new()));
+        assert_eq!(summary_and_description(&lines(&["Get a user"])), ("Get a user".to_string(), Vec::new()));
+        assert_eq!(
+            summary_and_description(&lines(&["Get a user", "Looks the user up by ID.", "# Responses", "- 200: OK"])),
+            ("Get a user".to_string(), lines(&["Looks the user up by ID."]))
+        );
+        // A doc comment that starts with a section has no summary
+        assert_eq!(summary_and_description(&lines(&["# Responses", "- 200: OK"])), (String::new(), Vec::new()));
+    }
+    
+    #[test]
+    fn test_extract_query_type() {
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            State(state): State<AppState>,
+            Query(query): Query<ListUsersQuery>
+        };
+        assert_eq!(extract_query_type(&inputs), Some("ListUsersQuery".to_string()));
         
-        let resp200 = &docs.responses[0];
-        assert_eq!(resp200.status_code, 200);
-        assert_eq!(resp200.description, "Success");
-        assert!(resp200.content.is_some());
+        // Maps of arbitrary query parameters have no fields to document
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            Query(params): Query<HashMap<String, String>>
+        };
+        assert_eq!(extract_query_type(&inputs), None);
+    }
+    
+    #[test]
+    fn test_extract_path_param_types() {
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            Path((org, id)): Path<(String, u32)>
+        };
+        assert_eq!(extract_path_param_types(&inputs), r#"["string","integer"]"#);
         
-        let content = resp200.content.as_ref().unwrap();
-        assert_eq!(content.media_type, "application/json");
-        assert_eq!(content.schema, Some("UserResponse".to_string()));
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            Path((org, id, active)): Path<(u64, f64, bool)>
+        };
+        assert_eq!(extract_path_param_types(&inputs), r#"["integer","number","boolean"]"#);
         
-        let resp404 = &docs.responses[1];
-        assert_eq!(resp404.status_code, 404);
-        assert_eq!(resp404.description, "Not found");
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            Path(id): Path<u32>
+        };
+        assert_eq!(extract_path_param_types(&inputs), r#"["integer"]"#);
+        
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            Path(params): Path<OrgUserPath>
+        };
+        assert_eq!(extract_path_param_types(&inputs), r#"{"struct":"OrgUserPath"}"#);
+        
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            Path(id): Path<String>
+        };
+        assert_eq!(extract_path_param_types(&inputs), r#"["string"]"#);
+        
+        let inputs: syn::punctuated::Punctuated<FnArg, syn::token::Comma> = parse_quote! {
+            Json(user): Json<User>
+        };
+        assert_eq!(extract_path_param_types(&inputs), "[]");
     }
     
     #[test]
-    fn test_extract_docs_with_examples() {
+    fn test_extract_docs_response_headers_and_schema() {
         let attrs = vec![
-            parse_quote!(#[doc = " Test endpoint"]),
+            parse_quote!(#[doc = " Submit job"]),
             parse_quote!(#[doc = " "]),
             parse_quote!(#[doc = " # Responses"]),
-            parse_quote!(#[doc = " - 200:"]),
-            parse_quote!(#[doc = "   description: Success"]),
-            parse_quote!(#[doc = "   examples:"]),
-            parse_quote!(#[doc = "     - name: success_example"]),
-            parse_quote!(#[doc = "       summary: Successful response"]),
-            parse_quote!(#[doc = r#"       value: {"status": "ok"}"#]),
+            parse_quote!(#[doc = " - 202:"]),
+            parse_quote!(#[doc = "   description: Job accepted"]),
+            parse_quote!(#[doc = "   headers:"]),
+            parse_quote!(#[doc = "     Location: URL of the job status resource"]),
+            parse_quote!(#[doc = "   content:"]),
+            parse_quote!(#[doc = "     application/json:"]),
+            parse_quote!(#[doc = "       schema: JobHandle"]),
+            parse_quote!(#[doc = " - 400: Invalid job"]),
         ];
         
         let docs = extract_docs(&attrs);
-        assert_eq!(docs.responses.len(), 1);
-        
-        let resp = &docs.responses[0];
-        assert!(resp.examples.is_some());
-        
-        let examples = resp.examples.as_ref().unwrap();
-        assert_eq!(examples.len(), 1);
+        assert_eq!(docs.responses.len(), 2);
+        assert_eq!(docs.responses[0].description, "Job accepted");
+        assert_eq!(docs.responses[0].headers, vec![("Location".to_string(), "URL of the job status resource".to_string())]);
+        assert!(docs.responses[1].headers.is_empty());
         
-        let example = &examples[0];
-        assert_eq!(example.name, "success_example");
-        assert_eq!(example.summary, Some("Successful response".to_string()));
-        assert_eq!(example.value, r#"{"status": "ok"}"#);
+        let details: serde_json::Value = serde_json::from_str(&response_details_json(&docs.responses)).unwrap();
+        assert_eq!(details["202"]["headers"]["Location"], "URL of the job status resource");
+        assert_eq!(details["202"]["schema"], "JobHandle");
+        assert!(details.get("400").is_none());
     }
     
     #[test]
-    fn test_extract_docs_empty() {
-        let attrs = vec![];
-        let docs = extract_docs(&attrs);
-        
-        assert_eq!(docs.summary, None);
-        assert_eq!(docs.description, None);
-        assert!(docs.parameters.is_empty());
-        assert!(docs.request_body.is_none());
-        assert!(docs.responses.is_empty());
+    fn test_is_complete_value() {
+        assert!(is_complete_value(r#"{"status": "ok"}"#));
+        assert!(is_complete_value("active"));
+        assert!(is_complete_value(r#"{"text": "a } inside a string"}"#));
+        assert!(!is_complete_value(""));
+        assert!(!is_complete_value("{"));
+        assert!(!is_complete_value(r#"{"items": ["a","#));
     }
-}
\ No newline at end of file
+}